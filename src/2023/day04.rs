@@ -54,29 +54,50 @@ impl Scratchcard {
     }
 
     fn parser<'i>() -> impl Parser<'i, Self> {
-        b"Card "
-            .then_skip_all(b' ')
-            .then_skip_all(parse::digit::<u8>())
-            .and_discard(b':')
-            .and_instead(
-                b' '.then_skip(b' ')
-                    .and_instead(parse::unsigned_int::<u32>())
-                    .repeat_fold(|| 0u128, |acc, curr| acc | 1 << curr)
-                    .capped_by(b" |"),
-            )
-            .and(
-                b' '.then_skip(b' ')
-                    .and_instead(parse::unsigned_int::<u32>())
-                    .repeat_fold(|| 0u128, |acc, curr| acc | 1 << curr),
-            )
-            .map(|(w, n)| Self {
-                numbers: n,
-                winners: w,
-            })
-            .then_skip(b'\n')
+        let number = || parse::token(parse::unsigned_int::<u32>());
+
+        parse::tokens_mode(
+            parse::keyword(b"Card")
+                .and_instead(number())
+                .and_discard(parse::token(b':'))
+                .and_instead(
+                    number()
+                        .repeat_fold(|| 0u128, |acc, curr| acc | 1 << curr)
+                        .capped_by(b'|'),
+                )
+                .and(number().repeat_fold(|| 0u128, |acc, curr| acc | 1 << curr)),
+        )
+        .map(|(w, n)| Self {
+            numbers: n,
+            winners: w,
+        })
+        .then_skip(b'\n')
     }
 
     fn parse_list(input: &[u8]) -> Vec<Scratchcard> {
         Self::parser().repeat().parse(input).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &[u8] = b"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    #[test]
+    fn p1_works_on_example() {
+        assert_eq!(p1(&Scratchcard::parse_list(EXAMPLE)), 13);
+    }
+
+    #[test]
+    fn p2_works_on_example() {
+        assert_eq!(p2(&Scratchcard::parse_list(EXAMPLE)), 30);
+    }
+}
@@ -55,18 +55,13 @@ impl Races {
     }
 
     fn parser<'i>() -> impl Parser<'i, Self> {
-        b"Time:"
-            .and_instead(
-                b' '.then_skip_all(b' ')
-                    .and_instead(parse::unsigned_int())
-                    .repeat(),
-            )
-            .and_discard(b"\nDistance:")
-            .and(
-                b' '.then_skip_all(b' ')
-                    .and_instead(parse::unsigned_int())
-                    .repeat(),
-            )
+        let numbers = || parse::token(parse::unsigned_int()).repeat();
+
+        parse::tokens_mode(parse::keyword(b"Time:").and_instead(numbers()))
+            .and_discard(b'\n')
+            .and(parse::tokens_mode(
+                parse::keyword(b"Distance:").and_instead(numbers()),
+            ))
             .map(|(times, distances)| Races { times, distances })
     }
 }
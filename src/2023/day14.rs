@@ -1,6 +1,7 @@
 use common::aoc::Day;
-use common::grid::Grid;
-use rustc_hash::FxHashMap;
+use common::grid::{Grid, GridRead, Transform};
+use common::utils::cycle::nth_value;
+use std::ops::IndexMut;
 
 const WALL: u8 = b'#';
 const FLOOR: u8 = b'.';
@@ -19,35 +20,20 @@ pub fn main(day: &mut Day, input: &[u8]) {
 fn p1(grid: &Grid<u8, Vec<u8>>) -> usize {
     let mut grid = grid.clone();
 
-    tilt(&mut grid, |x, y| (x, y - 1));
+    tilt(&mut grid);
     load(&grid)
 }
 
 fn p2(grid: &Grid<u8, Vec<u8>>) -> usize {
-    let mut grid = grid.clone();
-    let mut seen = FxHashMap::default();
-
-    let mut loads = Vec::with_capacity(256);
-    let mut cycle_len = 0;
-    let mut cycle_start = 0;
-
-    for n in 0..1000000000 {
-        tilt(&mut grid, |x, y| (x, y - 1));
-        tilt(&mut grid, |x, y| (x - 1, y));
-        tilt_rev(&mut grid, |x, y| (x, y + 1));
-        tilt_rev(&mut grid, |x, y| (x + 1, y));
-
-        loads.push(load(&grid));
-
-        if let Some(n2) = seen.insert(grid.clone(), n) {
-            cycle_len = n - n2;
-            cycle_start = n2;
-            break;
-        }
-    }
+    nth_value(grid.clone(), spin, load, 1000000000)
+}
 
-    let pos_in_cycle = (1000000000 - cycle_start) % cycle_len;
-    loads[cycle_start + pos_in_cycle - 1]
+/// Tilt the grid north, west, south and east.
+fn spin(grid: &mut Grid<u8, Vec<u8>>) {
+    tilt(grid);
+    tilt(&mut grid.view_mut(Transform::Transpose));
+    tilt(&mut grid.view_mut(Transform::FlipVertical));
+    tilt(&mut grid.view_mut(Transform::AntiTranspose));
 }
 
 fn load(grid: &Grid<u8, Vec<u8>>) -> usize {
@@ -64,50 +50,24 @@ fn load(grid: &Grid<u8, Vec<u8>>) -> usize {
     sum
 }
 
-fn tilt<M: Fn(usize, usize) -> (usize, usize)>(grid: &mut Grid<u8, Vec<u8>>, m: M) -> u32 {
+/// Tilt the grid north. Tilting a view of the grid tilts it in the other directions.
+fn tilt<G>(grid: &mut G) -> u32
+where
+    G: GridRead<u8> + IndexMut<(usize, usize), Output = u8>,
+{
     let mut movements = 0;
 
     for y in 1..grid.height() - 1 {
         for x in 1..grid.width() - 1 {
             if grid[(x, y)] == STONE {
-                let mut x = x;
-                let mut y = y;
-                let (mut nx, mut ny) = m(x, y);
-
-                while grid[(nx, ny)] == FLOOR {
-                    grid[(nx, ny)] = STONE;
-                    grid[(x, y)] = FLOOR;
-
-                    movements += 1;
-
-                    (x, y) = (nx, ny);
-                    (nx, ny) = m(x, y);
-                }
-            }
-        }
-    }
-
-    movements
-}
-
-fn tilt_rev<M: Fn(usize, usize) -> (usize, usize)>(grid: &mut Grid<u8, Vec<u8>>, m: M) -> u32 {
-    let mut movements = 0;
-
-    for y in (1..grid.height() - 1).rev() {
-        for x in (1..grid.width() - 1).rev() {
-            if grid[(x, y)] == STONE {
-                let mut x = x;
                 let mut y = y;
-                let (mut nx, mut ny) = m(x, y);
 
-                while grid[(nx, ny)] == FLOOR {
-                    grid[(nx, ny)] = STONE;
+                while grid[(x, y - 1)] == FLOOR {
+                    grid[(x, y - 1)] = STONE;
                     grid[(x, y)] = FLOOR;
 
                     movements += 1;
-
-                    (x, y) = (nx, ny);
-                    (nx, ny) = m(x, y);
+                    y -= 1;
                 }
             }
         }
@@ -1,4 +1,5 @@
 use common::aoc::Day;
+use common::geo::{Direction, Point};
 use common::grid::Grid;
 
 const EMPTY: u8 = b'.';
@@ -52,14 +53,15 @@ fn check_from(input: &Grid<u8, Vec<u8>>, start: (usize, usize), dir: Direction)
     let mut seen = vec![0u8; input.height() * input.width()];
     let mut stack = Vec::with_capacity(8);
 
-    stack.push((start, dir));
+    stack.push((Point::from(start), dir));
 
-    while let Some(((x, y), dir)) = stack.pop() {
-        let curr = input[(x, y)];
+    while let Some((pos, dir)) = stack.pop() {
+        let curr = input[pos];
         if curr == EDGE {
             continue;
         }
 
+        let [x, y] = *pos.coords();
         let i = (y * input.height()) + x;
         if seen[i] & 1 << dir as u8 != 0 {
             continue;
@@ -68,32 +70,32 @@ fn check_from(input: &Grid<u8, Vec<u8>>, start: (usize, usize), dir: Direction)
 
         match curr {
             MIRROR_TL_BR => {
-                let next_dir = dir.reflect_tl_br();
-                stack.push((next_dir.next(x, y), next_dir))
+                let next_dir = reflect_tl_br(dir);
+                stack.push((pos.step(next_dir), next_dir))
             }
 
             MIRROR_TR_BL => {
-                let next_dir = dir.reflect_tr_bl();
-                stack.push((next_dir.next(x, y), next_dir))
+                let next_dir = reflect_tr_bl(dir);
+                stack.push((pos.step(next_dir), next_dir))
             }
 
-            EMPTY => stack.push((dir.next(x, y), dir)),
+            EMPTY => stack.push((pos.step(dir), dir)),
 
             SPLITTER_H => {
-                if let Some((dir1, dir2)) = dir.split(false) {
-                    stack.push((dir1.next(x, y), dir1));
-                    stack.push((dir2.next(x, y), dir2));
+                if let Some((dir1, dir2)) = split(dir, false) {
+                    stack.push((pos.step(dir1), dir1));
+                    stack.push((pos.step(dir2), dir2));
                 } else {
-                    stack.push((dir.next(x, y), dir))
+                    stack.push((pos.step(dir), dir))
                 }
             }
 
             SPLITTER_V => {
-                if let Some((dir1, dir2)) = dir.split(true) {
-                    stack.push((dir1.next(x, y), dir1));
-                    stack.push((dir2.next(x, y), dir2));
+                if let Some((dir1, dir2)) = split(dir, true) {
+                    stack.push((pos.step(dir1), dir1));
+                    stack.push((pos.step(dir2), dir2));
                 } else {
-                    stack.push((dir.next(x, y), dir))
+                    stack.push((pos.step(dir), dir))
                 }
             }
 
@@ -104,52 +106,27 @@ fn check_from(input: &Grid<u8, Vec<u8>>, start: (usize, usize), dir: Direction)
     seen.iter().filter(|v| **v > 0).count()
 }
 
-#[derive(Copy, Clone)]
-enum Direction {
-    Up = 0,
-    Left = 1,
-    Right = 2,
-    Down = 3,
-}
-
-impl Direction {
-    fn next(&self, x: usize, y: usize) -> (usize, usize) {
-        match self {
-            Direction::Up => (x, y - 1),
-            Direction::Left => (x - 1, y),
-            Direction::Right => (x + 1, y),
-            Direction::Down => (x, y + 1),
-        }
-    }
-
-    fn reflect_tr_bl(self) -> Direction {
-        // /
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Left => Direction::Down,
-            Direction::Right => Direction::Up,
-            Direction::Down => Direction::Left,
-        }
+/// How `/` turns the beam.
+fn reflect_tr_bl(dir: Direction) -> Direction {
+    match dir.is_horizontal() {
+        true => dir.turn_left(),
+        false => dir.turn_right(),
     }
+}
 
-    fn reflect_tl_br(self) -> Direction {
-        // \
-        match self {
-            Direction::Up => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Right,
-        }
+/// How `\` turns the beam.
+fn reflect_tl_br(dir: Direction) -> Direction {
+    match dir.is_horizontal() {
+        true => dir.turn_right(),
+        false => dir.turn_left(),
     }
+}
 
-    fn split(self, hor: bool) -> Option<(Direction, Direction)> {
-        match (self, hor) {
-            (Direction::Up, false) => Some((Direction::Left, Direction::Right)),
-            (Direction::Down, false) => Some((Direction::Left, Direction::Right)),
-            (Direction::Left, true) => Some((Direction::Up, Direction::Down)),
-            (Direction::Right, true) => Some((Direction::Up, Direction::Down)),
-            _ => None,
-        }
+fn split(dir: Direction, hor: bool) -> Option<(Direction, Direction)> {
+    if dir.is_horizontal() == hor {
+        Some((dir.turn_left(), dir.turn_right()))
+    } else {
+        None
     }
 }
 
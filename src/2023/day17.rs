@@ -1,6 +1,6 @@
 use common::aoc::Day;
+use common::geo::Point;
 use common::grid::Grid;
-use common::search::{dijkstra, Search, WithCost};
 
 const BOUNDARY: u8 = 0;
 
@@ -15,112 +15,31 @@ pub fn main(day: &mut Day, input: &[u8]) {
 }
 
 fn p1(grid: &Grid<u8, Vec<u8>>) -> u32 {
-    let goal = (grid.width() - 2, grid.height() - 2);
-
-    dijkstra()
-        .with_initial_state(WithCost(((1usize, 1usize), Direction::Right, 3u8), 0u32))
-        .find(|s, WithCost(((x, y), dir, steps_left), cost)| {
-            let curr = grid[(x, y)];
-            if curr == BOUNDARY {
-                return None;
-            }
-            if (x, y) == goal {
-                return Some(cost);
-            }
-
-            let (left, right) = dir.left_right();
-            if steps_left > 0 {
-                let pos_ahead = dir.next(x, y);
-                let cost_ahead = cost + grid[pos_ahead] as u32;
-                s.add_state(WithCost((pos_ahead, dir, steps_left - 1), cost_ahead));
-            }
-
-            let pos_left = left.next(x, y);
-            let cost_left = cost + grid[pos_left] as u32;
-            let pos_right = right.next(x, y);
-            let cost_right = cost + grid[pos_right] as u32;
-
-            s.add_state(WithCost((pos_left, left, 2), cost_left));
-            s.add_state(WithCost((pos_right, right, 2), cost_right));
-
-            None
-        })
-        .unwrap()
+    find_path(grid, 1, 3)
 }
 
 fn p2(grid: &Grid<u8, Vec<u8>>) -> u32 {
-    let goal = (grid.width() - 2, grid.height() - 2);
-
-    dijkstra()
-        .with_initial_state(WithCost(((1usize, 1usize), Direction::Right, 0u8), 0u32))
-        .and_additional_state(WithCost(((1usize, 1usize), Direction::Down, 0u8), 0u32))
-        .find(|s, WithCost(((x, y), dir, steps_since), cost)| {
-            let curr = grid[(x, y)];
-            if curr == BOUNDARY {
-                return None;
-            }
-            if (x, y) == goal {
-                if steps_since < 4 {
-                    return None;
-                }
-
-                return Some(cost);
-            }
-
-            let (left, right) = dir.left_right();
-            if steps_since < 10 {
-                let pos_ahead = dir.next(x, y);
-                let cost_ahead = cost + grid[pos_ahead] as u32;
-                s.add_state(WithCost((pos_ahead, dir, steps_since + 1), cost_ahead));
-            }
-
-            if steps_since > 3 {
-                let pos_left = left.next(x, y);
-                let cost_left = cost + grid[pos_left] as u32;
-                let pos_right = right.next(x, y);
-                let cost_right = cost + grid[pos_right] as u32;
-
-                s.add_state(WithCost((pos_left, left, 1), cost_left));
-                s.add_state(WithCost((pos_right, right, 1), cost_right));
-            }
+    find_path(grid, 4, 10)
+}
 
-            None
-        })
-        .unwrap()
+fn find_path(grid: &Grid<u8, Vec<u8>>, min_run: u8, max_run: u8) -> u32 {
+    let goal = Point::new(grid.width() - 2, grid.height() - 2);
+
+    grid.path_finder(|_, _, v| match *v {
+        BOUNDARY => None,
+        v => Some(v as u32),
+    })
+    .with_runs(min_run, max_run)
+    .with_manhattan(1)
+    .find(Point::new(1, 1), goal)
+    .unwrap()
+    .cost
 }
 
 fn parse(input: &[u8]) -> Grid<u8, Vec<u8>> {
     Grid::parse_padded_map(input, BOUNDARY, |f| f - b'0')
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-enum Direction {
-    Up = 0,
-    Left = 1,
-    Right = 2,
-    Down = 3,
-}
-
-impl Direction {
-    fn next(&self, x: usize, y: usize) -> (usize, usize) {
-        match self {
-            Direction::Up => (x, y - 1),
-            Direction::Left => (x - 1, y),
-            Direction::Right => (x + 1, y),
-            Direction::Down => (x, y + 1),
-        }
-    }
-
-    fn left_right(&self) -> (Direction, Direction) {
-        match self {
-            Direction::Up => (Direction::Left, Direction::Right),
-            Direction::Left => (Direction::Down, Direction::Up),
-            Direction::Right => (Direction::Up, Direction::Down),
-            Direction::Down => (Direction::Right, Direction::Left),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
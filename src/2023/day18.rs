@@ -1,10 +1,8 @@
 use common::aoc::Day;
+use common::geo::{Direction, Point};
 use common::grid::Grid;
 use common::parse;
 use common::parse::Parser;
-use common::search::bfs;
-use common::search::Search;
-use std::ops::{Add, Sub};
 
 pub fn main(day: &mut Day, input: &[u8]) {
     let digs = day.prep("Parse", || Dig::parse_list(input));
@@ -26,7 +24,7 @@ fn p1_bfs(input: &[Dig]) -> u32 {
     let (mut max_x, mut max_y) = (0, 0);
     let (mut x, mut y) = (0, 0);
     for dig in input.iter() {
-        (x, y) = dig.dir.next(x, y, dig.steps as i32);
+        [x, y] = *Point::new(x, y).step_by(dig.dir, dig.steps as i32).coords();
 
         if x < min_x {
             min_x = x
@@ -44,15 +42,14 @@ fn p1_bfs(input: &[Dig]) -> u32 {
 
     let grid_width = (max_x - min_x) as usize + 2;
     let grid_height = (max_y - min_y) as usize + 2;
-    let mut x = (x - min_x) as usize + 1;
-    let mut y = (y - min_y) as usize + 1;
+    let mut pos = Point::new((x - min_x) as usize + 1, (y - min_y) as usize + 1);
     let mut grid: Grid<u8, Vec<u8>> = Grid::new_with_value(grid_width, grid_height, b'.');
-    grid[(x, y)] = b'#';
+    grid[pos] = b'#';
     let mut dug = 0;
     for dig in input.iter() {
         for _ in 0..dig.steps {
-            (x, y) = dig.dir.next(x, y, 1);
-            grid[(x, y)] = b'#';
+            pos = pos.step(dig.dir);
+            grid[pos] = b'#';
             dug += 1;
         }
     }
@@ -63,7 +60,7 @@ fn p1_bfs(input: &[Dig]) -> u32 {
             if let Some(first_trench) = row.iter().position(|v| *v == b'#') {
                 if first_trench == 0 {
                     if row[1] == b'.' {
-                        Some((1, y))
+                        Some(Point::new(1, y))
                     } else {
                         None
                     }
@@ -71,7 +68,7 @@ fn p1_bfs(input: &[Dig]) -> u32 {
                     && row[first_trench - 1] == b'.'
                     && row[first_trench + 1] == b'.'
                 {
-                    Some((first_trench + 1, y))
+                    Some(Point::new(first_trench + 1, y))
                 } else {
                     None
                 }
@@ -81,27 +78,13 @@ fn p1_bfs(input: &[Dig]) -> u32 {
         })
         .unwrap();
 
-    bfs()
-        .with_initial_state(point_inside)
-        .gather::<u32, _, _>(|s, (x, y)| {
-            let curr = grid[(x, y)];
-            if curr == b'.' {
-                s.add_state((x, y - 1));
-                s.add_state((x - 1, y));
-                s.add_state((x + 1, y));
-                s.add_state((x, y + 1));
-                Some(())
-            } else {
-                None
-            }
-        })
-        + dug
+    grid.flood_fill(point_inside, |v| *v == b'.').len() as u32 + dug
 }
 
 fn p1_shoelace(input: &[Dig]) -> i64 {
     let (mut x, mut y) = (0, 0);
     shoelace(input.iter().map(|dig| {
-        (x, y) = dig.dir.next(x, y, dig.steps as i64);
+        [x, y] = *Point::new(x, y).step_by(dig.dir, dig.steps as i64).coords();
         (x, y, dig.steps as i64)
     }))
 }
@@ -110,15 +93,9 @@ fn p2_shoelace(input: &[Dig]) -> i64 {
     let (mut x, mut y) = (0, 0);
     shoelace(input.iter().map(|dig| {
         let steps = (dig.color >> 4) as i64;
-        let dir = match dig.color % 4 {
-            0 => Direction::Right,
-            1 => Direction::Down,
-            2 => Direction::Left,
-            3 => Direction::Up,
-            _ => unreachable!(),
-        };
+        let dir = Direction::from_index((dig.color % 4) as usize);
 
-        (x, y) = dir.next(x, y, steps);
+        [x, y] = *Point::new(x, y).step_by(dir, steps).coords();
         (x, y, steps)
     }))
 }
@@ -164,49 +141,19 @@ impl Dig {
     }
 
     fn parser<'i>() -> impl Parser<'i, Self> {
-        parse::choice((
-            b'U'.map_to(Direction::Up),
-            b'L'.map_to(Direction::Left),
-            b'R'.map_to(Direction::Right),
-            b'D'.map_to(Direction::Down),
-        ))
-        .and_discard(b' ')
-        .and(parse::unsigned_int())
-        .and_discard(b" (#")
-        .and(
-            parse::hex_digit()
-                .repeat_n(6)
-                .map(|v: [u32; 6]| v.iter().fold(0, |c, n| c * 16 + *n)),
-        )
-        .and_discard(b')')
-        .map(|((dir, steps), color)| Self { dir, steps, color })
+        Direction::parser()
+            .and_discard(b' ')
+            .and(parse::unsigned_int())
+            .and_discard(b" (#")
+            .and(
+                parse::hex_digit()
+                    .repeat_n(6)
+                    .map(|v: [u32; 6]| v.iter().fold(0, |c, n| c * 16 + *n)),
+            )
+            .and_discard(b')')
+            .map(|((dir, steps), color)| Self { dir, steps, color })
     }
 }
-#[derive(Copy, Clone, Debug)]
-enum Direction {
-    Up = 0,
-    Left = 1,
-    Right = 2,
-    Down = 3,
-}
-
-impl Direction {
-    #[inline]
-    fn next<T: Copy + Add<T, Output = T> + Sub<T, Output = T>>(
-        &self,
-        x: T,
-        y: T,
-        steps: T,
-    ) -> (T, T) {
-        match self {
-            Direction::Up => (x, y - steps),
-            Direction::Left => (x - steps, y),
-            Direction::Right => (x + steps, y),
-            Direction::Down => (x, y + steps),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,6 @@
 use common::aoc::Day;
 use common::geo::Point;
-use common::grid::Grid;
+use common::grid::{Grid, Neighbourhood};
 use common::parse;
 use common::parse::Parser;
 use rustc_hash::FxHashSet;
@@ -36,13 +36,8 @@ fn p1(input: &Grid<u8, Vec<u8>>, target: u8) -> u32 {
         if steps == target {
             count += 1;
         } else {
-            for adjacent in pos.cardinals_offset_underflow(1) {
-                let [x, y] = adjacent.coords();
-                if *x == usize::MAX || *y == usize::MAX {
-                    continue;
-                }
-
-                if input[adjacent] != WALL {
+            for (adjacent, v) in input.neighbours(pos, Neighbourhood::Cardinal) {
+                if *v != WALL {
                     stack.push((adjacent, steps + 1))
                 }
             }
@@ -289,9 +289,7 @@ impl Slab {
 
     #[inline]
     fn parser<'i>() -> impl Parser<'i, Self> {
-        parse::unsigned_int()
-            .delimited_by(b',')
-            .repeat_n(3)
+        parse::coords(parse::unsigned_int(), b',')
             .delimited_by(b'~')
             .repeat_n(2)
             .map(|(a, b)| Self::new(a, b))
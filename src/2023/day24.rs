@@ -60,9 +60,9 @@ impl Hailstone<i64> {
 
     fn parser<'i>() -> impl Parser<'i, Self> {
         parse::signed_int()
-            .delimited_by(b", ")
+            .delimited_by(parse::token(b','))
             .repeat_n(3)
-            .delimited_by(b" @ ")
+            .delimited_by(parse::token(b'@'))
             .repeat_n(2)
             .map(|(pos, vel)| Self(pos, vel))
     }
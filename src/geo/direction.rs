@@ -0,0 +1,269 @@
+use crate::parse::{any_byte, Parser};
+
+/// Anything that points somewhere on a 2D grid, where y grows downwards.
+pub trait Heading: Copy {
+    /// The `(x, y)` step, each being -1, 0 or 1.
+    fn delta(&self) -> (i8, i8);
+}
+
+/// One of the four cardinal directions. The discriminants go clockwise from `Right`, which
+/// matches the `0-3` digits used for them in puzzle inputs.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Direction {
+    Right = 0,
+    Down = 1,
+    Left = 2,
+    Up = 3,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ];
+
+    #[inline]
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    #[inline]
+    pub fn from_index(index: usize) -> Direction {
+        Self::ALL[index % 4]
+    }
+
+    #[inline]
+    pub fn iter() -> impl Iterator<Item = Direction> {
+        Self::ALL.into_iter()
+    }
+
+    #[inline]
+    pub fn turn_right(&self) -> Direction {
+        Self::from_index(self.index() + 1)
+    }
+
+    #[inline]
+    pub fn turn_left(&self) -> Direction {
+        Self::from_index(self.index() + 3)
+    }
+
+    #[inline]
+    pub fn reverse(&self) -> Direction {
+        Self::from_index(self.index() + 2)
+    }
+
+    #[inline]
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
+
+    #[inline]
+    pub fn is_vertical(&self) -> bool {
+        !self.is_horizontal()
+    }
+
+    /// Read a direction from `U/D/L/R`, `N/S/E/W`, `^v<>` or `0-3`, where the digits go
+    /// clockwise from right.
+    pub fn from_byte(b: u8) -> Option<Direction> {
+        match b {
+            b'R' | b'E' | b'>' | b'0' => Some(Direction::Right),
+            b'D' | b'S' | b'v' | b'1' => Some(Direction::Down),
+            b'L' | b'W' | b'<' | b'2' => Some(Direction::Left),
+            b'U' | b'N' | b'^' | b'3' => Some(Direction::Up),
+            _ => None,
+        }
+    }
+
+    pub fn parser<'i>() -> impl Parser<'i, Direction> {
+        any_byte().filter_map(Self::from_byte)
+    }
+}
+
+impl Heading for Direction {
+    #[inline]
+    fn delta(&self) -> (i8, i8) {
+        match self {
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Up => (0, -1),
+        }
+    }
+}
+
+/// One of the eight directions including the diagonals, going clockwise from `Right`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Direction8 {
+    Right = 0,
+    DownRight = 1,
+    Down = 2,
+    DownLeft = 3,
+    Left = 4,
+    UpLeft = 5,
+    Up = 6,
+    UpRight = 7,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+        Direction8::Up,
+        Direction8::UpRight,
+    ];
+
+    #[inline]
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    #[inline]
+    pub fn from_index(index: usize) -> Direction8 {
+        Self::ALL[index % 8]
+    }
+
+    #[inline]
+    pub fn iter() -> impl Iterator<Item = Direction8> {
+        Self::ALL.into_iter()
+    }
+
+    /// Turn 45 degrees clockwise.
+    #[inline]
+    pub fn turn_right(&self) -> Direction8 {
+        Self::from_index(self.index() + 1)
+    }
+
+    /// Turn 45 degrees counter-clockwise.
+    #[inline]
+    pub fn turn_left(&self) -> Direction8 {
+        Self::from_index(self.index() + 7)
+    }
+
+    #[inline]
+    pub fn turn_right_90(&self) -> Direction8 {
+        Self::from_index(self.index() + 2)
+    }
+
+    #[inline]
+    pub fn turn_left_90(&self) -> Direction8 {
+        Self::from_index(self.index() + 6)
+    }
+
+    #[inline]
+    pub fn reverse(&self) -> Direction8 {
+        Self::from_index(self.index() + 4)
+    }
+
+    #[inline]
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+}
+
+impl Heading for Direction8 {
+    #[inline]
+    fn delta(&self) -> (i8, i8) {
+        match self {
+            Direction8::Right => (1, 0),
+            Direction8::DownRight => (1, 1),
+            Direction8::Down => (0, 1),
+            Direction8::DownLeft => (-1, 1),
+            Direction8::Left => (-1, 0),
+            Direction8::UpLeft => (-1, -1),
+            Direction8::Up => (0, -1),
+            Direction8::UpRight => (1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    #[inline]
+    fn from(dir: Direction) -> Self {
+        Self::from_index(dir.index() * 2)
+    }
+}
+
+impl TryFrom<Direction8> for Direction {
+    type Error = Direction8;
+
+    #[inline]
+    fn try_from(dir: Direction8) -> Result<Self, Self::Error> {
+        if dir.is_diagonal() {
+            Err(dir)
+        } else {
+            Ok(Direction::from_index(dir.index() / 2))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::Point;
+    use crate::parse::ParseResult;
+
+    #[test]
+    fn direction_turns() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert_eq!(Direction::Down.turn_left().turn_left(), Direction::Up);
+        assert!(Direction::Left.is_horizontal());
+        assert!(Direction::Up.is_vertical());
+
+        assert_eq!(Direction8::Up.turn_right(), Direction8::UpRight);
+        assert_eq!(Direction8::Right.turn_left(), Direction8::UpRight);
+        assert_eq!(Direction8::DownLeft.reverse(), Direction8::UpRight);
+        assert_eq!(Direction8::Up.turn_left_90(), Direction8::Left);
+        assert_eq!(Direction8::from(Direction::Left), Direction8::Left);
+        assert_eq!(Direction::try_from(Direction8::Up), Ok(Direction::Up));
+        assert_eq!(
+            Direction::try_from(Direction8::UpLeft),
+            Err(Direction8::UpLeft)
+        );
+    }
+
+    #[test]
+    fn direction_deltas_sum_to_zero() {
+        let sum4 = Direction::iter().fold(Point::new(0i32, 0), |p, d| p.step(d));
+        let sum8 = Direction8::iter().fold(Point::new(0i32, 0), |p, d| p.step(d));
+
+        assert_eq!(sum4, Point::new(0, 0));
+        assert_eq!(sum8, Point::new(0, 0));
+        assert_eq!(Direction8::iter().filter(|d| d.is_diagonal()).count(), 4);
+    }
+
+    #[test]
+    fn direction_parses_from_all_notations() {
+        for input in [
+            b"URDL".as_slice(),
+            b"NESW".as_slice(),
+            b"^>v<".as_slice(),
+            b"3012".as_slice(),
+        ] {
+            assert_eq!(
+                Direction::parser().repeat().parse(input),
+                ParseResult::Good(
+                    vec![
+                        Direction::Up,
+                        Direction::Right,
+                        Direction::Down,
+                        Direction::Left
+                    ],
+                    b"".as_slice()
+                )
+            );
+        }
+
+        assert_eq!(
+            Direction::parser().parse(b"x"),
+            ParseResult::new_bad("FilterMap function returned None")
+        );
+    }
+}
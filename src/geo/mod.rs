@@ -1,3 +1,5 @@
+mod direction;
 mod point;
 
+pub use direction::{Direction, Direction8, Heading};
 pub use point::Point;
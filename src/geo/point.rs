@@ -1,3 +1,4 @@
+use crate::geo::Heading;
 use num::traits::{WrappingAdd, WrappingSub};
 use num::One;
 use std::fmt::{Debug, Display, Formatter};
@@ -45,6 +46,38 @@ where
             Point::new(x, y + offset),
         ]
     }
+
+    /// Move `steps` in the direction. Like with `cardinals_offset`, this will underflow
+    /// unsigned coordinates when going up or left from zero.
+    #[inline]
+    pub fn step_by<H: Heading>(&self, heading: H, steps: T) -> Point<T> {
+        let [mut x, mut y] = *self.coords();
+        let (dx, dy) = heading.delta();
+
+        match dx {
+            1 => x = x + steps,
+            -1 => x = x - steps,
+            _ => {}
+        }
+        match dy {
+            1 => y = y + steps,
+            -1 => y = y - steps,
+            _ => {}
+        }
+
+        Point::new(x, y)
+    }
+}
+
+impl<T> Point<T>
+where
+    T: SimdElement,
+    T: Copy + Add<Output = T> + Sub<Output = T> + One,
+{
+    #[inline]
+    pub fn step<H: Heading>(&self, heading: H) -> Point<T> {
+        self.step_by(heading, T::one())
+    }
 }
 
 impl<T> Point<T>
@@ -0,0 +1,392 @@
+use crate::geo::Point;
+use crate::grid::{Grid, GridStorage, Neighbourhood};
+use crate::utils::cycle::nth_state_by_key;
+use rayon::prelude::*;
+use rustc_hash::FxHashSet;
+use smallvec::SmallVec;
+use std::hash::Hash;
+
+/// The neighbours of a cell, as given to the rule of an `Automaton`.
+pub struct Neighbours<'a, T> {
+    cells: SmallVec<[&'a T; 8]>,
+}
+
+impl<'a, T> Neighbours<'a, T> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.cells.iter().copied()
+    }
+
+    #[inline]
+    pub fn count_by<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.cells.iter().filter(|c| pred(c)).count()
+    }
+}
+
+impl<T> Neighbours<'_, T>
+where
+    T: PartialEq,
+{
+    #[inline]
+    pub fn count(&self, v: &T) -> usize {
+        self.cells.iter().filter(|c| **c == v).count()
+    }
+}
+
+/// A cellular automaton, where every cell is replaced at once by what the rule returns for it
+/// and its neighbours.
+pub struct Automaton<'n, F> {
+    neighbourhood: Neighbourhood<'n>,
+    wrapping: bool,
+    rule: F,
+}
+
+impl<'n, F> Automaton<'n, F> {
+    pub fn new(neighbourhood: Neighbourhood<'n>, rule: F) -> Self {
+        Self {
+            neighbourhood,
+            wrapping: false,
+            rule,
+        }
+    }
+
+    /// Let the neighbourhoods of grids wrap around the edges. It has no effect on point sets,
+    /// as they are unbounded.
+    pub fn wrapping(mut self) -> Self {
+        self.wrapping = true;
+        self
+    }
+
+    /// Start a double-buffered simulation from the board.
+    pub fn simulate<B>(self, board: B) -> Simulation<'n, B, F>
+    where
+        B: Board<F>,
+    {
+        Simulation {
+            next: board.clone(),
+            current: board,
+            automaton: self,
+            steps: 0,
+        }
+    }
+}
+
+impl<'n, F> Automaton<'n, F> {
+    #[inline]
+    fn apply<T, S>(&self, src: &Grid<T, S>, p: Point<usize>) -> T
+    where
+        S: GridStorage<T>,
+        F: Fn(&T, &Neighbours<T>) -> T,
+    {
+        let neighbours = Neighbours {
+            cells: src
+                .neighbour_points(p, self.neighbourhood, self.wrapping)
+                .map(|p| &src[p])
+                .collect(),
+        };
+
+        (self.rule)(&src[p], &neighbours)
+    }
+
+    /// Write the next generation of `src` into `dst`, which must be the same size.
+    pub fn step_grid<T, S>(&self, src: &Grid<T, S>, dst: &mut Grid<T, S>)
+    where
+        S: GridStorage<T>,
+        F: Fn(&T, &Neighbours<T>) -> T,
+    {
+        assert_eq!((src.width, src.height), (dst.width, dst.height));
+
+        for y in 0..src.height {
+            for x in 0..src.width {
+                dst[(x, y)] = self.apply(src, Point::new(x, y));
+            }
+        }
+    }
+
+    /// Like `step_grid`, but with the rows spread out over rayon's thread pool.
+    pub fn step_grid_par<T, S>(&self, src: &Grid<T, S>, dst: &mut Grid<T, S>)
+    where
+        S: GridStorage<T> + Sync,
+        T: Send + Sync,
+        F: Fn(&T, &Neighbours<T>) -> T + Sync,
+    {
+        assert_eq!((src.width, src.height), (dst.width, dst.height));
+
+        let width = src.width;
+        if width == 0 {
+            return;
+        }
+
+        dst.storage
+            .cell_range_mut(0, width * src.height)
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(y, row)| {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    *cell = self.apply(src, Point::new(x, y));
+                }
+            });
+    }
+}
+
+impl<'n, F> Automaton<'n, F>
+where
+    F: Fn(&bool, &Neighbours<bool>) -> bool,
+{
+    /// Write the next generation of a set of live cells into `dst`. Only the live cells and
+    /// their neighbours are checked, so a dead cell with no live neighbours must stay dead.
+    pub fn step_set(&self, src: &FxHashSet<Point<i64>>, dst: &mut FxHashSet<Point<i64>>) {
+        let offsets = self.neighbourhood.offsets();
+        let around = |p: Point<i64>| {
+            offsets
+                .iter()
+                .map(move |(dx, dy)| p + Point::new(*dx as i64, *dy as i64))
+        };
+
+        let candidates: FxHashSet<Point<i64>> = src
+            .iter()
+            .flat_map(|p| around(*p).chain(std::iter::once(*p)))
+            .collect();
+
+        dst.clear();
+        for p in candidates {
+            let neighbours = Neighbours {
+                cells: around(p)
+                    .map(|n| if src.contains(&n) { &true } else { &false })
+                    .collect(),
+            };
+
+            if (self.rule)(&src.contains(&p), &neighbours) {
+                dst.insert(p);
+            }
+        }
+    }
+}
+
+/// Something an `Automaton` can step.
+pub trait Board<F>: Clone + Eq {
+    /// What is stored to find repeated boards. It must tell any two different boards apart.
+    type Key: Hash + Eq;
+
+    fn step_into(&self, automaton: &Automaton<F>, next: &mut Self);
+
+    fn key(&self) -> Self::Key;
+}
+
+impl<T, S, F> Board<F> for Grid<T, S>
+where
+    S: GridStorage<T> + Clone + Hash + Eq,
+    T: Clone + Hash + Eq,
+    F: Fn(&T, &Neighbours<T>) -> T,
+{
+    type Key = Self;
+
+    #[inline]
+    fn step_into(&self, automaton: &Automaton<F>, next: &mut Self) {
+        automaton.step_grid(self, next)
+    }
+
+    #[inline]
+    fn key(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<F> Board<F> for FxHashSet<Point<i64>>
+where
+    F: Fn(&bool, &Neighbours<bool>) -> bool,
+{
+    /// The set itself can't be hashed, as it has no order, so the key is its sorted points.
+    type Key = Vec<[i64; 2]>;
+
+    #[inline]
+    fn step_into(&self, automaton: &Automaton<F>, next: &mut Self) {
+        automaton.step_set(self, next)
+    }
+
+    fn key(&self) -> Vec<[i64; 2]> {
+        let mut points: Vec<[i64; 2]> = self.iter().map(|p| *p.coords()).collect();
+        points.sort_unstable();
+        points
+    }
+}
+
+/// A running automaton with two boards, where each step writes into the one not in use.
+pub struct Simulation<'n, B, F> {
+    automaton: Automaton<'n, F>,
+    current: B,
+    next: B,
+    steps: usize,
+}
+
+impl<'n, B, F> Simulation<'n, B, F>
+where
+    B: Board<F>,
+{
+    #[inline]
+    pub fn board(&self) -> &B {
+        &self.current
+    }
+
+    #[inline]
+    pub fn into_board(self) -> B {
+        self.current
+    }
+
+    #[inline]
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn step(&mut self) -> &B {
+        self.current.step_into(&self.automaton, &mut self.next);
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.steps += 1;
+
+        &self.current
+    }
+
+    /// Step until `steps() == n`. If the board returns to an earlier state along the way, the
+    /// full cycles are skipped over.
+    pub fn run_to(&mut self, n: usize) -> &B {
+        if n > self.steps {
+            let automaton = &self.automaton;
+            let next = &mut self.next;
+
+            self.current = nth_state_by_key(
+                self.current.clone(),
+                |board: &mut B| {
+                    board.step_into(automaton, next);
+                    std::mem::swap(board, next);
+                },
+                B::key,
+                n - self.steps,
+            );
+            self.steps = n;
+        }
+
+        &self.current
+    }
+
+    /// Step until the board stops changing, returning the step where it first looked like the
+    /// one before it. It gives up with `None` after `max_steps` steps, which is what happens if
+    /// the board ends up oscillating.
+    pub fn run_until_stable(&mut self, max_steps: usize) -> Option<usize> {
+        for _ in 0..max_steps {
+            self.step();
+
+            if self.current == self.next {
+                return Some(self.steps);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'n, T, S, F> Simulation<'n, Grid<T, S>, F>
+where
+    S: GridStorage<T> + Clone + Hash + Eq + Sync,
+    T: Clone + Hash + Eq + Send + Sync,
+    F: Fn(&T, &Neighbours<T>) -> T + Sync,
+{
+    pub fn step_par(&mut self) -> &Grid<T, S> {
+        self.automaton.step_grid_par(&self.current, &mut self.next);
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.steps += 1;
+
+        &self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    fn life(alive: &bool, neighbours: &Neighbours<bool>) -> bool {
+        matches!((alive, neighbours.count(&true)), (true, 2) | (_, 3))
+    }
+
+    fn grid(input: &[u8]) -> Grid<bool> {
+        Grid::parser(any_byte().only_if(|v| *v != b'\n').map(|v| v == b'#'))
+            .parse(input)
+            .unwrap()
+    }
+
+    #[test]
+    fn grid_automaton_steps() {
+        let blinker = grid(b".....\n..#..\n..#..\n..#..\n.....\n");
+        let mut sim = Automaton::new(Neighbourhood::Moore, life).simulate(blinker.clone());
+
+        assert_eq!(sim.step().to_string(), ".....\n.....\n.###.\n.....\n.....");
+        assert_eq!(sim.step(), &blinker);
+        assert_eq!(
+            sim.step_par().to_string(),
+            ".....\n.....\n.###.\n.....\n....."
+        );
+        assert_eq!(sim.steps(), 3);
+
+        assert_eq!(sim.run_to(1_000_000_000), &blinker);
+        assert_eq!(sim.steps(), 1_000_000_000);
+        assert_eq!(sim.run_until_stable(10), None);
+        assert_eq!(sim.steps(), 1_000_000_010);
+    }
+
+    #[test]
+    fn grid_automaton_can_wrap_and_settle() {
+        let glider = grid(b".#...\n..#..\n###..\n.....\n.....\n");
+        let mut sim = Automaton::new(Neighbourhood::Moore, life)
+            .wrapping()
+            .simulate(glider.clone());
+        assert_eq!(sim.run_to(20), &glider);
+
+        let block = grid(b"......\n.##...\n.#....\n......\n");
+        let mut sim = Automaton::new(Neighbourhood::Moore, life).simulate(block);
+        assert_eq!(sim.run_until_stable(10), Some(2));
+        assert_eq!(sim.board().to_string(), "......\n.##...\n.##...\n......");
+    }
+
+    #[test]
+    fn grid_automaton_can_count_other_states() {
+        let forest: Grid<u8> = Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(b".#.\n|#|\n...\n")
+            .unwrap();
+        let rule = |v: &u8, n: &Neighbours<u8>| match *v {
+            b'.' if n.count(&b'|') >= 2 => b'|',
+            b'#' if n.count_by(|v| *v != b'.') < 4 => b'.',
+            v => v,
+        };
+
+        let mut sim = Automaton::new(Neighbourhood::Moore, rule).simulate(forest);
+        assert_eq!(sim.step().to_string(), "...\n|.|\n.|.");
+    }
+
+    #[test]
+    fn set_automaton_moves_gliders() {
+        let glider: FxHashSet<Point<i64>> = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+            .into_iter()
+            .map(Point::from)
+            .collect();
+
+        let mut sim = Automaton::new(Neighbourhood::Moore, life).simulate(glider.clone());
+        sim.run_to(400);
+
+        let expected: FxHashSet<Point<i64>> =
+            glider.iter().map(|p| *p + Point::new(100, 100)).collect();
+        assert_eq!(sim.board(), &expected);
+    }
+}
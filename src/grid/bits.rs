@@ -0,0 +1,514 @@
+use crate::geo::Point;
+use crate::parse::{line, Parser};
+use std::hash::{Hash, Hasher};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use std::sync::OnceLock;
+
+const WORD_BITS: usize = 64;
+
+/// A grid of booleans packed into `u64` words, one or more per row. Bit `x % 64` of word `x / 64`
+/// in a row is the cell at `x`, so whole rows can be compared, combined and shifted a word at a
+/// time. The bits past the width are always zero.
+///
+/// The columns are packed the same way the first time a column is asked for, and kept until the
+/// grid changes, so that they can be compared word by word too.
+#[derive(Clone, Debug)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    row_words: usize,
+    width: usize,
+    height: usize,
+    columns: OnceLock<Vec<u64>>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let row_words = width.div_ceil(WORD_BITS);
+
+        Self {
+            words: vec![0; row_words * height],
+            row_words,
+            width,
+            height,
+            columns: OnceLock::new(),
+        }
+    }
+
+    /// Parse a grid where `#` is set and anything else is not. It stops at an empty line.
+    #[inline]
+    pub fn parse(input: &[u8]) -> Self {
+        Self::parse_by(input, |v| v == b'#')
+    }
+
+    /// Parse a grid where the predicate decides which cells are set. It stops at an empty line.
+    pub fn parse_by<F>(input: &[u8], pred: F) -> Self
+    where
+        F: Fn(u8) -> bool,
+    {
+        let lines: Vec<&[u8]> = input
+            .split(|v| *v == b'\n')
+            .take_while(|line| !line.is_empty())
+            .collect();
+
+        Self::from_lines(&lines, pred)
+    }
+
+    /// A parser for a `#`/`.` grid that ends at an empty line or the end of the input, which
+    /// makes it easy to parse lists of grids.
+    pub fn parser<'i>() -> impl Parser<'i, BitGrid> {
+        line()
+            .only_if(|l| !l.is_empty())
+            .repeat::<Vec<_>>()
+            .map(|lines| Self::from_lines(&lines, |v| v == b'#'))
+    }
+
+    /// Build a grid from the lines, which is as wide as the longest of them.
+    fn from_lines<F>(lines: &[&[u8]], pred: F) -> Self
+    where
+        F: Fn(u8) -> bool,
+    {
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+
+        let mut grid = Self::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, v) in line.iter().enumerate() {
+                if pred(*v) {
+                    grid.set(x, y, true);
+                }
+            }
+        }
+
+        grid
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.words[(y * self.row_words) + (x / WORD_BITS)] & (1 << (x % WORD_BITS)) != 0
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, v: bool) {
+        self.columns.take();
+        let word = &mut self.words[(y * self.row_words) + (x / WORD_BITS)];
+        if v {
+            *word |= 1 << (x % WORD_BITS);
+        } else {
+            *word &= !(1 << (x % WORD_BITS));
+        }
+    }
+
+    #[inline]
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        self.columns.take();
+        self.words[(y * self.row_words) + (x / WORD_BITS)] ^= 1 << (x % WORD_BITS);
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.columns.take();
+        self.words.fill(0);
+    }
+
+    /// The words of a row.
+    #[inline]
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.row_words..(y + 1) * self.row_words]
+    }
+
+    #[inline]
+    pub fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        self.columns.take();
+        &mut self.words[y * self.row_words..(y + 1) * self.row_words]
+    }
+
+    /// The words of a column, packed like a row with bit `y` for the cell at `y`.
+    #[inline]
+    pub fn column(&self, x: usize) -> &[u64] {
+        let column_words = self.height.div_ceil(WORD_BITS);
+        &self.columns()[x * column_words..(x + 1) * column_words]
+    }
+
+    /// Swap rows and columns. The packed columns are the rows of the result, and the other way
+    /// around.
+    pub fn transposed(&self) -> BitGrid {
+        BitGrid {
+            words: self.columns().to_vec(),
+            row_words: self.height.div_ceil(WORD_BITS),
+            width: self.height,
+            height: self.width,
+            columns: OnceLock::from(self.words.clone()),
+        }
+    }
+
+    #[inline]
+    pub fn count_ones(&self) -> u32 {
+        self.words.iter().map(|w| w.count_ones()).sum()
+    }
+
+    #[inline]
+    pub fn row_count_ones(&self, y: usize) -> u32 {
+        self.row(y).iter().map(|w| w.count_ones()).sum()
+    }
+
+    #[inline]
+    pub fn column_count_ones(&self, x: usize) -> u32 {
+        self.column(x).iter().map(|w| w.count_ones()).sum()
+    }
+
+    #[inline]
+    pub fn rows_equal(&self, a: usize, b: usize) -> bool {
+        self.row(a) == self.row(b)
+    }
+
+    #[inline]
+    pub fn columns_equal(&self, a: usize, b: usize) -> bool {
+        self.column(a) == self.column(b)
+    }
+
+    /// The number of cells that differ between two rows.
+    #[inline]
+    pub fn row_difference(&self, a: usize, b: usize) -> u32 {
+        self.row(a)
+            .iter()
+            .zip(self.row(b).iter())
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum()
+    }
+
+    /// The number of cells that differ between two columns.
+    #[inline]
+    pub fn column_difference(&self, a: usize, b: usize) -> u32 {
+        self.column(a)
+            .iter()
+            .zip(self.column(b).iter())
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum()
+    }
+
+    /// Iterate over the positions of the set cells in reading order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = Point<usize>> + '_ {
+        (0..self.height).flat_map(move |y| {
+            self.row(y).iter().enumerate().flat_map(move |(i, word)| {
+                let mut word = *word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(Point::new((i * WORD_BITS) + bit, y))
+                })
+            })
+        })
+    }
+
+    /// Move every cell one step toward `x = 0`. Cells shifted out of the grid are lost.
+    pub fn shift_left(&self) -> BitGrid {
+        let mut res = self.clone();
+        for y in 0..self.height {
+            let row = res.row_mut(y);
+            for i in 0..row.len() {
+                let carry = row.get(i + 1).map(|w| w << (WORD_BITS - 1)).unwrap_or(0);
+                row[i] = (row[i] >> 1) | carry;
+            }
+        }
+
+        res
+    }
+
+    /// Move every cell one step away from `x = 0`. Cells shifted out of the grid are lost.
+    pub fn shift_right(&self) -> BitGrid {
+        let mut res = self.clone();
+        for y in 0..self.height {
+            let row = res.row_mut(y);
+            for i in (0..row.len()).rev() {
+                let carry = if i > 0 {
+                    row[i - 1] >> (WORD_BITS - 1)
+                } else {
+                    0
+                };
+                row[i] = (row[i] << 1) | carry;
+            }
+        }
+
+        res.mask_overflow();
+        res
+    }
+
+    /// Move every cell one step toward `y = 0`. Cells shifted out of the grid are lost.
+    pub fn shift_up(&self) -> BitGrid {
+        let mut res = BitGrid::new(self.width, self.height);
+        if self.height > 0 {
+            let len = self.words.len() - self.row_words;
+            res.words[..len].copy_from_slice(&self.words[self.row_words..]);
+        }
+
+        res
+    }
+
+    /// Move every cell one step away from `y = 0`. Cells shifted out of the grid are lost.
+    pub fn shift_down(&self) -> BitGrid {
+        let mut res = BitGrid::new(self.width, self.height);
+        if self.height > 0 {
+            let len = self.words.len() - self.row_words;
+            res.words[self.row_words..].copy_from_slice(&self.words[..len]);
+        }
+
+        res
+    }
+
+    /// Render the grid as `#` and `.`, with one line per row.
+    pub fn render(&self) -> String {
+        let mut res = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            if y > 0 {
+                res.push('\n');
+            }
+            for x in 0..self.width {
+                res.push(if self.get(x, y) { '#' } else { '.' });
+            }
+        }
+
+        res
+    }
+
+    /// Pack the columns, or get them from the last time if the grid has not changed since.
+    fn columns(&self) -> &[u64] {
+        self.columns.get_or_init(|| {
+            let column_words = self.height.div_ceil(WORD_BITS);
+            let mut columns = vec![0; column_words * self.width];
+            for p in self.iter_ones() {
+                let [x, y] = *p.coords();
+                columns[(x * column_words) + (y / WORD_BITS)] |= 1 << (y % WORD_BITS);
+            }
+
+            columns
+        })
+    }
+
+    #[inline]
+    fn mask_overflow(&mut self) {
+        let rem = self.width % WORD_BITS;
+        if rem != 0 {
+            let mask = (1u64 << rem) - 1;
+            for y in 0..self.height {
+                self.words[(y * self.row_words) + self.row_words - 1] &= mask;
+            }
+        }
+    }
+
+    #[inline]
+    fn zip_words<F>(&mut self, rhs: &BitGrid, f: F)
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        assert_eq!(
+            (self.width, self.height),
+            (rhs.width, rhs.height),
+            "BitGrids must have the same size"
+        );
+
+        self.columns.take();
+
+        for (a, b) in self.words.iter_mut().zip(rhs.words.iter()) {
+            *a = f(*a, *b);
+        }
+    }
+}
+
+/// The packed columns are left out, since they only depend on the rows.
+impl PartialEq for BitGrid {
+    fn eq(&self, other: &Self) -> bool {
+        (self.width, self.height) == (other.width, other.height) && self.words == other.words
+    }
+}
+
+impl Eq for BitGrid {}
+
+impl Hash for BitGrid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.words.hash(state);
+        self.row_words.hash(state);
+        self.width.hash(state);
+        self.height.hash(state);
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident, $f:expr) => {
+        impl $op_assign<&BitGrid> for BitGrid {
+            #[inline]
+            fn $fn_assign(&mut self, rhs: &BitGrid) {
+                self.zip_words(rhs, $f);
+            }
+        }
+
+        impl $op<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+
+            #[inline]
+            fn $fn(self, rhs: &BitGrid) -> BitGrid {
+                let mut res = self.clone();
+                res.zip_words(rhs, $f);
+                res
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut res = self.clone();
+        res.columns.take();
+        for word in res.words.iter_mut() {
+            *word = !*word;
+        }
+
+        res.mask_overflow();
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIRROR: &[u8] = b"#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..###
+#.#.##.#.
+";
+
+    #[test]
+    fn bit_grid_parses_and_compares() {
+        let grid = BitGrid::parse(MIRROR);
+
+        assert_eq!(grid.width(), 9);
+        assert_eq!(grid.height(), 7);
+        assert!(grid.get(0, 0));
+        assert!(!grid.get(1, 0));
+        assert_eq!(grid.row(0), &[0b011001101]);
+        assert_eq!(grid.render().as_bytes(), &MIRROR[..MIRROR.len() - 1]);
+
+        assert!(grid.rows_equal(2, 3));
+        assert!(!grid.rows_equal(1, 2));
+        assert_eq!(grid.row_difference(0, 5), 2);
+        assert!(grid.columns_equal(4, 5));
+        assert!(!grid.columns_equal(0, 1));
+        assert_eq!(grid.column_difference(0, 1), 2);
+        assert_eq!(grid.column(0), &[0b1001101]);
+
+        let transposed = grid.transposed();
+        assert!(transposed.rows_equal(4, 5));
+        assert_eq!(transposed.transposed(), grid);
+
+        assert_eq!(grid.count_ones(), 29);
+        assert_eq!(grid.row_count_ones(2), 3);
+        assert_eq!(grid.column_count_ones(2), 5);
+        assert_eq!(grid.iter_ones().count(), 29);
+        assert_eq!(grid.iter_ones().next(), Some(Point::new(0, 0)));
+    }
+
+    #[test]
+    fn bit_grid_columns_follow_changes() {
+        let mut grid = BitGrid::new(70, 130);
+        grid.set(3, 0, true);
+        grid.set(3, 129, true);
+        grid.set(5, 64, true);
+        assert_eq!(grid.column(3), &[1, 0, 1 << 1]);
+        assert_eq!(grid.column_difference(3, 5), 3);
+
+        grid.set(5, 0, true);
+        grid.toggle(5, 129);
+        grid.set(5, 64, false);
+        assert!(grid.columns_equal(3, 5));
+        assert_eq!(grid.column_count_ones(5), 2);
+
+        grid.row_mut(0)[1] = 1 << 5;
+        assert_eq!(grid.column(69), &[1, 0, 0]);
+        assert_eq!(grid.column_difference(3, 69), 1);
+
+        let mut other = grid.clone();
+        other ^= &grid;
+        assert_eq!(other.column_count_ones(3), 0);
+        assert_eq!((!&other).column_count_ones(3), 130);
+        assert_eq!(other, BitGrid::new(70, 130));
+
+        let transposed = grid.transposed();
+        assert_eq!(transposed.row(3), grid.column(3));
+        assert_eq!(transposed.column(0), grid.row(0));
+    }
+
+    #[test]
+    fn bit_grid_shifts_and_combines() {
+        let grid = BitGrid::parse(b"#..#\n.##.\n");
+
+        assert_eq!(grid.shift_left().render(), "..#.\n##..");
+        assert_eq!(grid.shift_right().render(), ".#..\n..##");
+        assert_eq!(grid.shift_up().render(), ".##.\n....");
+        assert_eq!(grid.shift_down().render(), "....\n#..#");
+        assert_eq!((!&grid).render(), ".##.\n#..#");
+
+        let other = BitGrid::parse(b"##..\n..##\n");
+        assert_eq!((&grid & &other).render(), "#...\n..#.");
+        assert_eq!((&grid | &other).render(), "##.#\n.###");
+        assert_eq!((&grid ^ &other).render(), ".#.#\n.#.#");
+
+        let mut acc = grid.clone();
+        acc |= &grid.shift_left();
+        acc ^= &other;
+        assert_eq!(acc.render(), ".###\n##.#");
+    }
+
+    #[test]
+    fn bit_grid_spans_several_words() {
+        let mut grid = BitGrid::new(130, 2);
+        grid.set(63, 0, true);
+        grid.set(129, 1, true);
+
+        let right = grid.shift_right();
+        assert!(right.get(64, 0));
+        assert_eq!(right.count_ones(), 1);
+
+        let left = grid.shift_left();
+        assert!(left.get(62, 0));
+        assert!(left.get(128, 1));
+        assert_eq!((!&grid).count_ones(), 258);
+
+        let parsed = BitGrid::parser()
+            .delimited_by(b'\n')
+            .repeat::<Vec<_>>()
+            .parse(b"#.\n.#\n\n##\n");
+        assert_eq!(parsed.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn bit_grid_can_be_empty() {
+        let grid = BitGrid::new(0, 3);
+        assert_eq!((!&grid).count_ones(), 0);
+        assert_eq!(grid.shift_right().count_ones(), 0);
+        assert_eq!(grid.shift_down(), grid);
+        assert_eq!(grid.row(1), &[] as &[u64]);
+        assert_eq!(grid.iter_ones().count(), 0);
+    }
+}
@@ -1,14 +1,281 @@
-use std::marker::PhantomData;
-use crate::grid::Grid;
-
-pub struct ChunkedGrid<T, S> {
+use crate::geo::Point;
+use crate::grid::{Grid, GridStorage};
+use rustc_hash::FxHashMap;
+use std::ops::{Index, IndexMut};
 
+/// An unbounded grid with signed coordinates. It is split into square chunks that are only
+/// allocated when a cell in them is written, and the cells that were never written have the
+/// default value.
+///
+/// In tiled mode, the grid is an endless repetition of a base grid instead, and the chunks
+/// start out as copies of the tiles they cover.
+#[derive(Clone, Debug)]
+pub struct ChunkedGrid<T, S = Vec<T>> {
+    chunks: Vec<Chunk<T, S>>,
+    chunk_index: FxHashMap<Point<i64>, usize>,
+    chunk_size: i64,
+    default: T,
+    base: Option<Grid<T, S>>,
+    bounds: Option<(Point<i64>, Point<i64>)>,
 }
 
+#[derive(Clone, Debug)]
 struct Chunk<T, S> {
-    neighs: [usize; 4],
-    top: isize,
-    left: isize,
+    top: i64,
+    left: i64,
     grid: Grid<T, S>,
-    spooky: PhantomData<T>
-}
\ No newline at end of file
+    /// One bit per cell in reading order, set for the cells that have been written.
+    written: Vec<u64>,
+}
+
+impl<T, S> ChunkedGrid<T, S>
+where
+    S: GridStorage<T>,
+    T: Copy,
+{
+    pub fn new(chunk_size: usize, default: T) -> Self {
+        assert!(chunk_size > 0, "chunk size must not be zero");
+
+        Self {
+            chunks: Vec::new(),
+            chunk_index: FxHashMap::default(),
+            chunk_size: chunk_size as i64,
+            default,
+            base: None,
+            bounds: None,
+        }
+    }
+
+    /// Make an endless grid that repeats the base grid in every direction, with the base grid's
+    /// top left corner at `(0, 0)`. Writes only change the written cell, not the other tiles.
+    pub fn tiled(chunk_size: usize, base: Grid<T, S>) -> Self {
+        let default = base[(0, 0)];
+        let mut grid = Self::new(chunk_size, default);
+        grid.base = Some(base);
+        grid
+    }
+
+    #[inline]
+    pub fn default_value(&self) -> T {
+        self.default
+    }
+
+    #[inline]
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// The top left and bottom right corners of the cells that have been written to, inclusive.
+    #[inline]
+    pub fn bounding_box(&self) -> Option<(Point<i64>, Point<i64>)> {
+        self.bounds
+    }
+
+    #[inline]
+    pub fn get(&self, p: Point<i64>) -> &T {
+        match self.chunk_index.get(&self.chunk_pos(p)) {
+            Some(index) => {
+                let chunk = &self.chunks[*index];
+                let [x, y] = *p.coords();
+                &chunk.grid[((x - chunk.left) as usize, (y - chunk.top) as usize)]
+            }
+            None => self.unwritten(p),
+        }
+    }
+
+    /// Get the cell for writing, allocating its chunk if needed. This counts as a write for the
+    /// bounding box.
+    pub fn get_mut(&mut self, p: Point<i64>) -> &mut T {
+        let [x, y] = *p.coords();
+        self.bounds = Some(match self.bounds {
+            Some((tl, br)) => (
+                Point::new(tl.coords()[0].min(x), tl.coords()[1].min(y)),
+                Point::new(br.coords()[0].max(x), br.coords()[1].max(y)),
+            ),
+            None => (p, p),
+        });
+
+        let chunk_pos = self.chunk_pos(p);
+        let index = match self.chunk_index.get(&chunk_pos) {
+            Some(index) => *index,
+            None => self.allocate(chunk_pos),
+        };
+
+        let chunk = &mut self.chunks[index];
+        let (x, y) = ((x - chunk.left) as usize, (y - chunk.top) as usize);
+        let bit = (y * chunk.grid.width()) + x;
+        chunk.written[bit / 64] |= 1 << (bit % 64);
+
+        &mut chunk.grid[(x, y)]
+    }
+
+    #[inline]
+    pub fn set(&mut self, p: Point<i64>, v: T) {
+        *self.get_mut(p) = v;
+    }
+
+    /// Iterate over the cells that have been written, chunk by chunk.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<i64>, &T)> {
+        self.chunks.iter().flat_map(|chunk| {
+            let width = chunk.grid.width();
+            chunk.written.iter().enumerate().flat_map(move |(i, word)| {
+                let mut word = *word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+
+                    let bit = (i * 64) + word.trailing_zeros() as usize;
+                    word &= word - 1;
+
+                    let (x, y) = (bit % width, bit / width);
+                    Some((
+                        Point::new(chunk.left + x as i64, chunk.top + y as i64),
+                        &chunk.grid[(x, y)],
+                    ))
+                })
+            })
+        })
+    }
+
+    /// Count the cells in the allocated chunks that match, whether they were written or not.
+    #[inline]
+    pub fn count_by<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.chunks.iter().map(|c| c.grid.count_by(&pred)).sum()
+    }
+
+    #[inline]
+    fn chunk_pos(&self, p: Point<i64>) -> Point<i64> {
+        let [x, y] = *p.coords();
+        Point::new(x.div_euclid(self.chunk_size), y.div_euclid(self.chunk_size))
+    }
+
+    #[inline]
+    fn unwritten(&self, p: Point<i64>) -> &T {
+        match &self.base {
+            Some(base) => {
+                let [x, y] = *p.coords();
+                &base[(
+                    x.rem_euclid(base.width() as i64) as usize,
+                    y.rem_euclid(base.height() as i64) as usize,
+                )]
+            }
+            None => &self.default,
+        }
+    }
+
+    fn allocate(&mut self, chunk_pos: Point<i64>) -> usize {
+        let [cx, cy] = *chunk_pos.coords();
+        let size = self.chunk_size as usize;
+        let (left, top) = (cx * self.chunk_size, cy * self.chunk_size);
+
+        let mut grid = Grid::new_with_value(size, size, self.default);
+        if self.base.is_some() {
+            for y in 0..size {
+                for x in 0..size {
+                    grid[(x, y)] = *self.unwritten(Point::new(left + x as i64, top + y as i64));
+                }
+            }
+        }
+
+        self.chunks.push(Chunk {
+            top,
+            left,
+            grid,
+            written: vec![0; (size * size).div_ceil(64)],
+        });
+        self.chunk_index.insert(chunk_pos, self.chunks.len() - 1);
+
+        self.chunks.len() - 1
+    }
+}
+
+impl<T, S> Index<Point<i64>> for ChunkedGrid<T, S>
+where
+    S: GridStorage<T>,
+    T: Copy,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: Point<i64>) -> &Self::Output {
+        self.get(index)
+    }
+}
+
+impl<T, S> IndexMut<Point<i64>> for ChunkedGrid<T, S>
+where
+    S: GridStorage<T>,
+    T: Copy,
+{
+    #[inline]
+    fn index_mut(&mut self, index: Point<i64>) -> &mut Self::Output {
+        self.get_mut(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    #[test]
+    fn chunked_grid_grows_on_demand() {
+        let mut grid: ChunkedGrid<u8> = ChunkedGrid::new(4, b'.');
+
+        assert_eq!(grid[Point::new(-100, 3)], b'.');
+        assert_eq!(grid.chunk_count(), 0);
+        assert_eq!(grid.bounding_box(), None);
+
+        grid[Point::new(-5, -1)] = b'#';
+        grid.set(Point::new(2, 9), b'@');
+        grid[Point::new(3, 8)] = b'#';
+
+        assert_eq!(grid[Point::new(-5, -1)], b'#');
+        assert_eq!(grid[Point::new(-4, -1)], b'.');
+        assert_eq!(grid[Point::new(2, 9)], b'@');
+        assert_eq!(grid.chunk_count(), 2);
+        assert_eq!(
+            grid.bounding_box(),
+            Some((Point::new(-5, -1), Point::new(3, 9)))
+        );
+        assert_eq!(grid.count_by(|v| *v == b'#'), 2);
+
+        let mut written: Vec<_> = grid.iter().collect();
+        written.sort_by_key(|(p, _)| *p.coords());
+        assert_eq!(
+            written,
+            vec![
+                (Point::new(-5, -1), &b'#'),
+                (Point::new(2, 9), &b'@'),
+                (Point::new(3, 8), &b'#'),
+            ]
+        );
+    }
+
+    #[test]
+    fn tiled_grid_repeats_the_base() {
+        let base: Grid<u8> = Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(b"ab\ncd\ne#\n")
+            .unwrap();
+        let mut grid = ChunkedGrid::tiled(5, base);
+
+        assert_eq!(grid[Point::new(0, 0)], b'a');
+        assert_eq!(grid[Point::new(3, 4)], b'd');
+        assert_eq!(grid[Point::new(-1, -1)], b'#');
+        assert_eq!(grid[Point::new(-2, -6)], b'a');
+
+        grid[Point::new(-1, -1)] = b'.';
+        assert_eq!(grid[Point::new(-1, -1)], b'.');
+        assert_eq!(grid[Point::new(-2, -1)], b'e');
+        assert_eq!(grid[Point::new(1, 2)], b'#');
+        assert_eq!(grid.count_by(|v| *v == b'#'), 5);
+        assert_eq!(
+            grid.iter().collect::<Vec<_>>(),
+            vec![(Point::new(-1, -1), &b'.')]
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::ds::Graph;
+use crate::geo::{Direction, Point};
+use crate::grid::{Grid, GridStorage};
+
+/// An edge made by `Grid::compress_corridors`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Corridor {
+    /// The number of steps from one node to the other.
+    pub length: u32,
+    /// False if walking this way would go against a one-way cell.
+    pub allowed: bool,
+}
+
+impl<T, S> Grid<T, S>
+where
+    S: GridStorage<T>,
+    T: Copy,
+{
+    /// Contract the corridors of a maze into a graph. The nodes are the junctions, i.e. the
+    /// passable cells with three or more passable neighbours, plus the cells the `interesting`
+    /// predicate picks out. They are added in reading order with the cell value as their value.
+    ///
+    /// Each corridor gets an edge in both directions with its length, and dead ends are left
+    /// out. A one-way cell can only be left in its direction, and the edges that break that
+    /// are kept, but with `allowed` set to false.
+    pub fn compress_corridors<const CAP: usize, PF, IF, OF>(
+        &self,
+        passable: PF,
+        interesting: IF,
+        one_way: OF,
+    ) -> Graph<Point<usize>, T, Corridor, CAP>
+    where
+        PF: Fn(&T) -> bool,
+        IF: Fn(Point<usize>, &T) -> bool,
+        OF: Fn(&T) -> Option<Direction>,
+    {
+        let passable = &passable;
+        let exits = |p: Point<usize>| {
+            Direction::ALL.into_iter().filter_map(move |dir| {
+                let next = self.step(p, dir)?;
+                if passable(&self[next]) {
+                    Some((dir, next))
+                } else {
+                    None
+                }
+            })
+        };
+        let allowed = |p: Point<usize>, dir: Direction| match one_way(&self[p]) {
+            Some(one_way_dir) => one_way_dir == dir,
+            None => true,
+        };
+
+        let mut graph = Graph::new();
+        let mut indices: Grid<Option<usize>> = Grid::new_with_value(self.width, self.height, None);
+        for (p, v) in self.iter() {
+            if passable(v) && (interesting(p, v) || exits(p).count() > 2) {
+                indices[p] = Some(graph.create_node(p, *v));
+            }
+        }
+
+        for index in 0..graph.len() {
+            let start = *graph.node(index).unwrap().0;
+
+            for (dir, first) in exits(start) {
+                let (mut prev, mut current) = (start, first);
+                let mut corridor = Corridor {
+                    length: 1,
+                    allowed: allowed(start, dir),
+                };
+
+                let target = loop {
+                    if let Some(target) = indices[current] {
+                        break Some(target);
+                    }
+
+                    match exits(current).find(|(_, next)| *next != prev) {
+                        Some((dir, next)) => {
+                            corridor.length += 1;
+                            corridor.allowed &= allowed(current, dir);
+                            (prev, current) = (current, next);
+                        }
+                        None => break None,
+                    }
+                };
+
+                if let Some(target) = target {
+                    graph.connect(index, target, corridor);
+                }
+            }
+        }
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    const MAZE: &[u8] = b"#S#######
+#.......#
+#.#.#.#.#
+#.#.>.#.#
+#.#####.#
+#......E#
+#########
+";
+
+    fn one_way(v: &u8) -> Option<Direction> {
+        match v {
+            b'>' => Some(Direction::Right),
+            b'<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn corridors_are_compressed() {
+        let grid: Grid<u8> = Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(MAZE)
+            .unwrap();
+
+        let graph: Graph<Point<usize>, u8, Corridor, 4> =
+            grid.compress_corridors(|v| *v != b'#', |_, v| *v == b'S' || *v == b'E', one_way);
+
+        assert_eq!(
+            graph.nodes().map(|(_, p, v)| (*p, *v)).collect::<Vec<_>>(),
+            vec![
+                (Point::new(1, 0), b'S'),
+                (Point::new(1, 1), b'.'),
+                (Point::new(3, 1), b'.'),
+                (Point::new(5, 1), b'.'),
+                (Point::new(7, 5), b'E'),
+            ]
+        );
+
+        let edges = |index: usize| {
+            let mut edges: Vec<_> = graph
+                .edges_from(index)
+                .map(|(c, target, _)| (*target, c.length, c.allowed))
+                .collect();
+            edges.sort();
+            edges
+        };
+
+        assert_eq!(edges(0), vec![(1, 1, true)]);
+        assert_eq!(edges(1), vec![(0, 1, true), (2, 2, true), (4, 10, true)]);
+        assert_eq!(edges(2), vec![(1, 2, true), (3, 2, true), (3, 6, true)]);
+        assert_eq!(edges(3), vec![(2, 2, true), (2, 6, false), (4, 6, true)]);
+        assert_eq!(edges(4), vec![(1, 10, true), (3, 6, true)]);
+    }
+}
@@ -0,0 +1,378 @@
+use crate::grid::{Grid, GridStorage};
+use crate::parse::Parser;
+use std::ops::{Index, IndexMut};
+
+/// Which neighbours are adjacent to a cell, by what they share with it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Adjacency {
+    /// Cells that differ in one coordinate, 6 of them in 3D.
+    Face,
+    /// Cells that differ in at most two coordinates, 18 of them in 3D.
+    Edge,
+    /// Every cell around it, 26 of them in 3D.
+    Corner,
+}
+
+impl Adjacency {
+    #[inline]
+    fn max_changed(&self, dimensions: usize) -> usize {
+        match self {
+            Adjacency::Face => 1,
+            Adjacency::Edge => 2,
+            Adjacency::Corner => dimensions,
+        }
+    }
+
+    /// The offsets to the neighbours in `D` dimensions, each coordinate being -1, 0 or 1. This
+    /// allocates, so keep the result around and use `GridN::neighbours_by_offsets` if it's needed
+    /// for many cells.
+    pub fn offsets<const D: usize>(&self) -> Vec<[i8; D]> {
+        self.iter_offsets::<D>().collect()
+    }
+
+    /// Like `offsets`, but they are worked out as they are iterated over.
+    pub fn iter_offsets<const D: usize>(&self) -> impl Iterator<Item = [i8; D]> {
+        let max_changed = self.max_changed(D);
+
+        (0..3usize.pow(D as u32))
+            .map(|mut n| {
+                let mut offset = [0i8; D];
+                for o in offset.iter_mut() {
+                    *o = (n % 3) as i8 - 1;
+                    n /= 3;
+                }
+
+                offset
+            })
+            .filter(move |offset| {
+                let changed = offset.iter().filter(|o| **o != 0).count();
+                changed > 0 && changed <= max_changed
+            })
+    }
+}
+
+/// A grid with `D` dimensions, stored with the first coordinate changing the fastest.
+#[derive(Eq, PartialEq, Debug, Clone, Hash)]
+pub struct GridN<T, const D: usize, S = Vec<T>> {
+    storage: S,
+    default: T,
+    size: [usize; D],
+}
+
+pub type Grid3<T, S = Vec<T>> = GridN<T, 3, S>;
+
+impl<T, const D: usize, S> GridN<T, D, S>
+where
+    S: GridStorage<T>,
+{
+    #[inline]
+    pub fn size(&self) -> [usize; D] {
+        self.size
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.size.iter().product()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn contains(&self, p: &[usize; D]) -> bool {
+        p.iter().zip(self.size.iter()).all(|(v, s)| v < s)
+    }
+
+    #[inline]
+    fn index_of(&self, p: &[usize; D]) -> usize {
+        p.iter()
+            .zip(self.size.iter())
+            .rev()
+            .fold(0, |acc, (v, s)| (acc * s) + v)
+    }
+
+    #[inline]
+    fn point_of(&self, mut index: usize) -> [usize; D] {
+        let mut p = [0; D];
+        for (v, s) in p.iter_mut().zip(self.size.iter()) {
+            *v = index % s;
+            index /= s;
+        }
+
+        p
+    }
+
+    #[inline]
+    pub fn get(&self, p: &[usize; D]) -> Option<&T> {
+        if self.contains(p) {
+            Some(&self.storage[self.index_of(p)])
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = ([usize; D], &T)> {
+        self.storage
+            .cell_range(0, self.len())
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (self.point_of(i), v))
+    }
+
+    #[inline]
+    pub fn count_by<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.storage
+            .cell_range(0, self.len())
+            .iter()
+            .filter(|c| pred(*c))
+            .count()
+    }
+
+    /// Iterate over the neighbours of the cell that are inside the grid.
+    pub fn neighbours(
+        &self,
+        p: [usize; D],
+        adjacency: Adjacency,
+    ) -> impl Iterator<Item = ([usize; D], &T)> {
+        adjacency
+            .iter_offsets::<D>()
+            .filter_map(move |offset| self.offset_cell(p, &offset))
+    }
+
+    /// Like `neighbours`, but with offsets from `Adjacency::offsets` that can be reused between
+    /// cells.
+    pub fn neighbours_by_offsets<'a>(
+        &'a self,
+        p: [usize; D],
+        offsets: &'a [[i8; D]],
+    ) -> impl Iterator<Item = ([usize; D], &'a T)> {
+        offsets
+            .iter()
+            .filter_map(move |offset| self.offset_cell(p, offset))
+    }
+
+    #[inline]
+    fn offset_cell(&self, p: [usize; D], offset: &[i8; D]) -> Option<([usize; D], &T)> {
+        let mut np = p;
+        for (v, o) in np.iter_mut().zip(offset.iter()) {
+            *v = v.checked_add_signed(*o as isize)?;
+        }
+
+        self.get(&np).map(|v| (np, v))
+    }
+}
+
+impl<T, const D: usize, S> GridN<T, D, S>
+where
+    S: GridStorage<T>,
+    T: Eq,
+{
+    #[inline]
+    pub fn find(&self, needle: &T) -> Option<[usize; D]> {
+        self.storage
+            .cell_range(0, self.len())
+            .iter()
+            .position(|c| c.eq(needle))
+            .map(|i| self.point_of(i))
+    }
+}
+
+impl<T, const D: usize, S> GridN<T, D, S>
+where
+    S: GridStorage<T>,
+    T: Copy,
+{
+    #[inline]
+    pub fn new_with_value(size: [usize; D], default: T) -> Self {
+        Self {
+            storage: S::create(size.iter().product(), default),
+            default,
+            size,
+        }
+    }
+
+    #[inline]
+    pub fn fill(&mut self, v: T) {
+        let len = self.len();
+        self.storage.cell_range_mut(0, len).fill(v);
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        let len = self.len();
+        self.storage.cell_range_mut(0, len).fill(self.default);
+    }
+
+    /// Copy a 2D slice of the grid, with the two axes becoming `x` and `y`. The other
+    /// coordinates are taken from `fixed`.
+    pub fn plane(&self, (x_axis, y_axis): (usize, usize), fixed: [usize; D]) -> Grid<T, Vec<T>> {
+        let (width, height) = (self.size[x_axis], self.size[y_axis]);
+        let mut grid = Grid::new_with_value(width, height, self.default);
+        let mut p = fixed;
+
+        for y in 0..height {
+            for x in 0..width {
+                p[x_axis] = x;
+                p[y_axis] = y;
+                grid[(x, y)] = self[p];
+            }
+        }
+
+        grid
+    }
+}
+
+impl<T, S> GridN<T, 3, S>
+where
+    S: GridStorage<T>,
+    T: Copy,
+{
+    /// Copy the `x`/`y` layer at `z` into a 2D grid.
+    #[inline]
+    pub fn layer(&self, z: usize) -> Grid<T, Vec<T>> {
+        self.plane((0, 1), [0, 0, z])
+    }
+
+    /// Parse layers of 2D grids separated by empty lines, stacking them along `z`. All layers
+    /// must have the same size.
+    pub fn layers_parser<'i, P>(cell_parser: P) -> impl Parser<'i, Self>
+    where
+        P: Parser<'i, T>,
+        T: Default,
+    {
+        Grid::<T, Vec<T>>::parser(cell_parser)
+            .repeat::<Vec<_>>()
+            .filter_map(|layers| {
+                let (width, height) = (layers[0].width(), layers[0].height());
+                if layers
+                    .iter()
+                    .any(|l| l.width() != width || l.height() != height)
+                {
+                    return None;
+                }
+
+                let mut grid = Self::new_with_value([width, height, layers.len()], T::default());
+                for (z, layer) in layers.iter().enumerate() {
+                    for (p, v) in layer.iter() {
+                        let [x, y] = *p.coords();
+                        grid[[x, y, z]] = *v;
+                    }
+                }
+
+                Some(grid)
+            })
+    }
+}
+
+impl<T, const D: usize, S> Index<[usize; D]> for GridN<T, D, S>
+where
+    S: GridStorage<T>,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: [usize; D]) -> &Self::Output {
+        &self.storage[self.index_of(&index)]
+    }
+}
+
+impl<T, const D: usize, S> IndexMut<[usize; D]> for GridN<T, D, S>
+where
+    S: GridStorage<T>,
+{
+    #[inline]
+    fn index_mut(&mut self, index: [usize; D]) -> &mut Self::Output {
+        let index = self.index_of(&index);
+        &mut self.storage[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    #[test]
+    fn adjacency_has_the_right_counts() {
+        assert_eq!(Adjacency::Face.offsets::<3>().len(), 6);
+        assert_eq!(Adjacency::Edge.offsets::<3>().len(), 18);
+        assert_eq!(Adjacency::Corner.offsets::<3>().len(), 26);
+        assert_eq!(Adjacency::Corner.offsets::<4>().len(), 80);
+        assert_eq!(Adjacency::Face.offsets::<2>().len(), 4);
+    }
+
+    #[test]
+    fn grid_n_indexes_and_finds_neighbours() {
+        let mut grid: Grid3<u8> = GridN::new_with_value([3, 4, 5], 0);
+        grid[[1, 2, 3]] = 7;
+        grid[[0, 0, 0]] = 1;
+        grid[[2, 3, 4]] = 2;
+
+        assert_eq!(grid.len(), 60);
+        assert_eq!(grid[[1, 2, 3]], 7);
+        assert_eq!(grid.get(&[3, 0, 0]), None);
+        assert_eq!(grid.find(&7), Some([1, 2, 3]));
+        assert_eq!(grid.iter().nth(59), Some(([2, 3, 4], &2)));
+        assert_eq!(grid.count_by(|v| *v > 0), 3);
+
+        assert_eq!(grid.neighbours([0, 0, 0], Adjacency::Face).count(), 3);
+        assert_eq!(grid.neighbours([0, 0, 0], Adjacency::Edge).count(), 6);
+        assert_eq!(grid.neighbours([0, 0, 0], Adjacency::Corner).count(), 7);
+        assert_eq!(grid.neighbours([1, 1, 1], Adjacency::Corner).count(), 26);
+
+        let offsets = Adjacency::Edge.offsets::<3>();
+        assert!(grid
+            .neighbours_by_offsets([1, 1, 1], &offsets)
+            .eq(grid.neighbours([1, 1, 1], Adjacency::Edge)));
+        assert_eq!(
+            grid.neighbours([1, 1, 3], Adjacency::Face)
+                .filter(|(_, v)| **v == 7)
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>(),
+            vec![[1, 2, 3]]
+        );
+
+        let mut hyper: GridN<bool, 4> = GridN::new_with_value([3, 3, 3, 3], false);
+        hyper[[1, 1, 1, 1]] = true;
+        assert_eq!(
+            hyper
+                .neighbours([0, 0, 0, 0], Adjacency::Corner)
+                .filter(|(_, v)| **v)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn grid_n_parses_and_slices_layers() {
+        let cell = any_byte().only_if(|v| *v != b'\n');
+        let grid = Grid3::<u8>::layers_parser(cell)
+            .parse(b"#..\n.#.\n\n...\n..#\n\n#.#\n...\n")
+            .unwrap();
+
+        assert_eq!(grid.size(), [3, 2, 3]);
+        assert_eq!(grid[[2, 1, 1]], b'#');
+        assert_eq!(grid.count_by(|v| *v == b'#'), 5);
+
+        let layer = grid.layer(2);
+        assert_eq!(layer.row(0), b"#.#");
+        assert_eq!(layer.row(1), b"...");
+
+        let side = grid.plane((2, 0), [0, 1, 0]);
+        assert_eq!(side.width(), 3);
+        assert_eq!(side.height(), 3);
+        assert_eq!(side.row(1), b"#..");
+        assert_eq!(side.row(2), b".#.");
+
+        assert_eq!(
+            Grid3::<u8>::layers_parser(cell).parse(b"#.\n.#\n\n...\n"),
+            crate::parse::ParseResult::new_bad("FilterMap function returned None")
+        );
+    }
+}
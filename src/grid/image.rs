@@ -0,0 +1,285 @@
+use crate::geo::Point;
+use crate::grid::{Grid, GridStorage};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub type Rgb = [u8; 3];
+
+/// An RGB image for dumping puzzle states, where each cell becomes a `scale` by `scale` square.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    pixels: Vec<Rgb>,
+    width: usize,
+    height: usize,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            pixels: vec![background; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Paint every cell of the grid with the colour the palette gives it.
+    pub fn from_grid<T, S, F>(grid: &Grid<T, S>, scale: usize, palette: F) -> Self
+    where
+        S: GridStorage<T>,
+        F: Fn(&T) -> Rgb,
+    {
+        let mut image = Self::new(grid.width() * scale, grid.height() * scale, [0; 3]);
+        for (p, v) in grid.iter() {
+            image.fill_cell(p, scale, palette(v));
+        }
+
+        image
+    }
+
+    /// Paint the points on a background, with the image being just large enough to fit them.
+    pub fn from_points<I, T, F>(points: I, background: Rgb, scale: usize, palette: F) -> Self
+    where
+        I: IntoIterator<Item = (Point<usize>, T)>,
+        F: Fn(&T) -> Rgb,
+    {
+        let points: Vec<_> = points.into_iter().collect();
+        let (width, height) = points.iter().fold((0, 0), |(w, h), (p, _)| {
+            let [x, y] = *p.coords();
+            (w.max(x + 1), h.max(y + 1))
+        });
+
+        let mut image = Self::new(width * scale, height * scale, background);
+        for (p, v) in points.iter() {
+            image.fill_cell(*p, scale, palette(v));
+        }
+
+        image
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[(y * self.width) + x]
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        self.pixels[(y * self.width) + x] = colour;
+    }
+
+    fn fill_cell(&mut self, p: Point<usize>, scale: usize, colour: Rgb) {
+        let [x, y] = *p.coords();
+        for py in (y * scale)..((y + 1) * scale) {
+            let start = (py * self.width) + (x * scale);
+            self.pixels[start..start + scale].fill(colour);
+        }
+    }
+
+    /// Write the image as a binary PPM (P6), which most image viewers and `ffmpeg` can read.
+    pub fn write_ppm<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(self.pixels.as_flattened())
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_ppm(&mut w)?;
+        w.flush()
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png<W: Write>(&self, w: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(w, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(self.pixels.as_flattened())
+            .map_err(io::Error::other)
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_png(&mut w)?;
+        w.flush()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ImageFormat {
+    Ppm,
+    #[cfg(feature = "png")]
+    Png,
+}
+
+impl ImageFormat {
+    #[inline]
+    fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            #[cfg(feature = "png")]
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+/// Writes one numbered image per frame into a directory, e.g. `frame-00000.ppm`, so that an
+/// algorithm can dump its state at every step and have it turned into an animation later.
+pub struct FrameWriter {
+    dir: PathBuf,
+    prefix: String,
+    format: ImageFormat,
+    frames: usize,
+}
+
+impl FrameWriter {
+    /// Set up the writer, creating the directory if it does not exist.
+    pub fn new<P: AsRef<Path>>(dir: P, prefix: &str) -> io::Result<Self> {
+        std::fs::create_dir_all(dir.as_ref())?;
+
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            format: ImageFormat::Ppm,
+            frames: 0,
+        })
+    }
+
+    pub fn with_format(mut self, format: ImageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    #[inline]
+    pub fn frame_count(&self) -> usize {
+        self.frames
+    }
+
+    /// Write the next frame, returning the path it was written to.
+    pub fn write(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self.dir.join(format!(
+            "{}{:05}.{}",
+            self.prefix,
+            self.frames,
+            self.format.extension()
+        ));
+
+        match self.format {
+            ImageFormat::Ppm => image.save_ppm(&path)?,
+            #[cfg(feature = "png")]
+            ImageFormat::Png => image.save_png(&path)?,
+        }
+
+        self.frames += 1;
+        Ok(path)
+    }
+
+    #[inline]
+    pub fn write_grid<T, S, F>(
+        &mut self,
+        grid: &Grid<T, S>,
+        scale: usize,
+        palette: F,
+    ) -> io::Result<PathBuf>
+    where
+        S: GridStorage<T>,
+        F: Fn(&T) -> Rgb,
+    {
+        self.write(&Image::from_grid(grid, scale, palette))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    const WHITE: Rgb = [255, 255, 255];
+    const BLACK: Rgb = [0, 0, 0];
+    const RED: Rgb = [255, 0, 0];
+
+    fn palette(v: &u8) -> Rgb {
+        match v {
+            b'#' => BLACK,
+            b'O' => RED,
+            _ => WHITE,
+        }
+    }
+
+    #[test]
+    fn grid_can_be_written_as_ppm() {
+        let grid: Grid<u8> = Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(b"#.\nO#\n")
+            .unwrap();
+
+        let mut ppm = Vec::new();
+        Image::from_grid(&grid, 1, palette)
+            .write_ppm(&mut ppm)
+            .unwrap();
+        assert_eq!(&ppm[..11], b"P6\n2 2\n255\n");
+        assert_eq!(&ppm[11..], [BLACK, WHITE, RED, BLACK].as_flattened());
+
+        let image = Image::from_grid(&grid, 3, palette);
+        assert_eq!((image.width(), image.height()), (6, 6));
+        assert_eq!(image.get(2, 2), BLACK);
+        assert_eq!(image.get(3, 2), WHITE);
+        assert_eq!(image.get(0, 5), RED);
+        assert_eq!(image.get(5, 5), BLACK);
+    }
+
+    #[test]
+    fn points_are_painted_on_a_background() {
+        let image = Image::from_points(
+            [(Point::new(3, 0), 1u32), (Point::new(1, 2), 2)],
+            WHITE,
+            2,
+            |v| [*v as u8 * 100; 3],
+        );
+
+        assert_eq!((image.width(), image.height()), (8, 6));
+        assert_eq!(image.get(7, 1), [100; 3]);
+        assert_eq!(image.get(2, 5), [200; 3]);
+        assert_eq!(image.get(0, 0), WHITE);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn image_can_be_written_as_png() {
+        let mut data = Vec::new();
+        Image::new(3, 2, RED).write_png(&mut data).unwrap();
+
+        assert_eq!(&data[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn frames_are_numbered() {
+        let dir = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        let mut frames = FrameWriter::new(&dir, "tilt-").unwrap();
+
+        let mut grid: Grid<u8> = Grid::new_with_value(2, 1, b'.');
+        frames.write_grid(&grid, 1, palette).unwrap();
+        grid[(1, 0)] = b'#';
+        let path = frames.write_grid(&grid, 1, palette).unwrap();
+
+        assert_eq!(frames.frame_count(), 2);
+        assert_eq!(path, dir.join("tilt-00001.ppm"));
+        assert_eq!(
+            std::fs::read(&path).unwrap(),
+            [b"P6\n2 1\n255\n".as_slice(), [WHITE, BLACK].as_flattened()].concat()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
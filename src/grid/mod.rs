@@ -1,11 +1,36 @@
 use crate::geo::Point;
 use crate::parse::{everything, line, ParseResult, Parser};
 use crate::utils::gather_target::GatherTarget;
+use crate::utils::scan::count_byte;
+pub use automaton::{Automaton, Board, Neighbours, Simulation};
+pub use bits::BitGrid;
+pub use chunked::ChunkedGrid;
+pub use corridors::Corridor;
+pub use grid_n::{Adjacency, Grid3, GridN};
+pub use image::{FrameWriter, Image, ImageFormat, Rgb};
+pub use neighbours::Neighbourhood;
+pub use pathfinding::{GridPath, PathFinder, PathState};
+pub use regions::{Component, Components};
+pub use render::{Colour, Glyph, Renderer};
+pub use sparse::SparseGrid;
 use std::hash::{Hash, Hasher};
 use std::ops::{Index, IndexMut};
 pub use storage::GridStorage;
-
+pub use view::{GridRead, GridView, GridViewMut, Transform};
+
+mod automaton;
+mod bits;
+mod chunked;
+mod corridors;
+mod grid_n;
+mod image;
+mod neighbours;
+mod pathfinding;
+mod regions;
+mod render;
+mod sparse;
 mod storage;
+mod view;
 
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub struct Grid<T, S = Vec<T>> {
@@ -130,7 +155,7 @@ where
             .map(move |(width, mut body)| {
                 let line_parser = line();
 
-                let mut height = body.iter().filter(|v| **v == b'\n').count();
+                let mut height = count_byte(body, b'\n');
                 if *body.last().unwrap() != b'\n' {
                     height += 1;
                 }
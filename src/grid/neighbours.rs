@@ -0,0 +1,275 @@
+use crate::geo::{Heading, Point};
+use crate::grid::{Grid, GridStorage};
+
+const CARDINAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const DIAGONAL: [(isize, isize); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+const MOORE: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Which cells around a cell count as its neighbours.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Neighbourhood<'o> {
+    /// Up, left, right and down, in the same order as `Point::cardinals`.
+    Cardinal,
+    /// The four corners, in reading order.
+    Diagonal,
+    /// All eight cells around it, in reading order.
+    Moore,
+    /// Any `(x, y)` offsets, in the order given.
+    Custom(&'o [(isize, isize)]),
+}
+
+impl<'o> Neighbourhood<'o> {
+    #[inline]
+    pub fn offsets(&self) -> &'o [(isize, isize)] {
+        match self {
+            Neighbourhood::Cardinal => &CARDINAL,
+            Neighbourhood::Diagonal => &DIAGONAL,
+            Neighbourhood::Moore => &MOORE,
+            Neighbourhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+impl<T, S> Grid<T, S>
+where
+    S: GridStorage<T>,
+{
+    /// Move `p` by the offset, returning `None` if it ends up outside of the grid. With
+    /// `wrapping`, it comes in from the other side instead, but an empty grid still has nowhere
+    /// to go.
+    #[inline]
+    pub fn offset_point(
+        &self,
+        p: Point<usize>,
+        (dx, dy): (isize, isize),
+        wrapping: bool,
+    ) -> Option<Point<usize>> {
+        let [x, y] = *p.coords();
+
+        if wrapping {
+            if self.width == 0 || self.height == 0 {
+                return None;
+            }
+
+            let (w, h) = (self.width as isize, self.height as isize);
+            Some(Point::new(
+                (x as isize + dx).rem_euclid(w) as usize,
+                (y as isize + dy).rem_euclid(h) as usize,
+            ))
+        } else {
+            let (x, y) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            if x < self.width && y < self.height {
+                Some(Point::new(x, y))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Take a step from `p`, returning `None` if it would leave the grid.
+    #[inline]
+    pub fn step<H: Heading>(&self, p: Point<usize>, heading: H) -> Option<Point<usize>> {
+        let (dx, dy) = heading.delta();
+        self.offset_point(p, (dx as isize, dy as isize), false)
+    }
+
+    /// Iterate over the cells from `p` in the direction, not including `p` itself, until the
+    /// edge of the grid.
+    #[inline]
+    pub fn ray<H: Heading>(
+        &self,
+        p: Point<usize>,
+        heading: H,
+    ) -> impl Iterator<Item = (Point<usize>, &T)> + use<'_, H, T, S> {
+        std::iter::successors(self.step(p, heading), move |p| self.step(*p, heading))
+            .map(|p| (p, &self[p]))
+    }
+
+    /// The positions of the neighbours of `p`, where the ones outside of the grid are skipped or
+    /// wrapped around.
+    #[inline]
+    pub fn neighbour_points<'o>(
+        &self,
+        p: Point<usize>,
+        neighbourhood: Neighbourhood<'o>,
+        wrapping: bool,
+    ) -> impl Iterator<Item = Point<usize>> + use<'_, 'o, T, S> {
+        neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |offset| self.offset_point(p, *offset, wrapping))
+    }
+
+    /// Iterate over the neighbours of `p` that are inside the grid.
+    #[inline]
+    pub fn neighbours<'o>(
+        &self,
+        p: Point<usize>,
+        neighbourhood: Neighbourhood<'o>,
+    ) -> impl Iterator<Item = (Point<usize>, &T)> + use<'_, 'o, T, S> {
+        self.neighbour_points(p, neighbourhood, false)
+            .map(|p| (p, &self[p]))
+    }
+
+    /// Iterate over the neighbours of `p`, where the neighbours past an edge are taken from
+    /// the opposite edge.
+    #[inline]
+    pub fn neighbours_wrapping<'o>(
+        &self,
+        p: Point<usize>,
+        neighbourhood: Neighbourhood<'o>,
+    ) -> impl Iterator<Item = (Point<usize>, &T)> + use<'_, 'o, T, S> {
+        self.neighbour_points(p, neighbourhood, true)
+            .map(|p| (p, &self[p]))
+    }
+
+    /// Call `f` with each neighbour of `p` that is inside the grid.
+    pub fn neighbours_mut<F>(&mut self, p: Point<usize>, neighbourhood: Neighbourhood, mut f: F)
+    where
+        F: FnMut(Point<usize>, &mut T),
+    {
+        for offset in neighbourhood.offsets() {
+            if let Some(np) = self.offset_point(p, *offset, false) {
+                f(np, &mut self[np]);
+            }
+        }
+    }
+
+    /// Call `f` with each neighbour of `p`, wrapping around the edges.
+    pub fn neighbours_wrapping_mut<F>(
+        &mut self,
+        p: Point<usize>,
+        neighbourhood: Neighbourhood,
+        mut f: F,
+    ) where
+        F: FnMut(Point<usize>, &mut T),
+    {
+        for offset in neighbourhood.offsets() {
+            if let Some(np) = self.offset_point(p, *offset, true) {
+                f(np, &mut self[np]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::{Direction, Direction8};
+    use crate::parse::{any_byte, Parser};
+
+    fn grid() -> Grid<u8> {
+        Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(b"abc\ndef\nghi\njkl\n")
+            .unwrap()
+    }
+
+    fn values<'a>(iter: impl Iterator<Item = (Point<usize>, &'a u8)>) -> String {
+        iter.map(|(_, v)| *v as char).collect()
+    }
+
+    #[test]
+    fn neighbours_are_bounds_checked() {
+        let grid = grid();
+
+        assert_eq!(
+            values(grid.neighbours(Point::new(1, 1), Neighbourhood::Cardinal)),
+            "bdfh"
+        );
+        assert_eq!(
+            values(grid.neighbours(Point::new(1, 1), Neighbourhood::Diagonal)),
+            "acgi"
+        );
+        assert_eq!(
+            values(grid.neighbours(Point::new(1, 1), Neighbourhood::Moore)),
+            "abcdfghi"
+        );
+        assert_eq!(
+            values(grid.neighbours(Point::new(0, 0), Neighbourhood::Moore)),
+            "bde"
+        );
+        assert_eq!(
+            values(grid.neighbours(Point::new(2, 3), Neighbourhood::Cardinal)),
+            "ik"
+        );
+        assert_eq!(
+            grid.neighbours(Point::new(0, 0), Neighbourhood::Cardinal)
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(0, 1)]
+        );
+
+        let knight = [(1, 2), (2, 1), (-1, 2), (-2, -1)];
+        assert_eq!(
+            values(grid.neighbours(Point::new(0, 0), Neighbourhood::Custom(&knight))),
+            "hf"
+        );
+    }
+
+    #[test]
+    fn grid_can_be_stepped_through() {
+        let grid = grid();
+
+        assert_eq!(
+            grid.step(Point::new(1, 1), Direction::Up),
+            Some(Point::new(1, 0))
+        );
+        assert_eq!(grid.step(Point::new(1, 0), Direction::Up), None);
+        assert_eq!(grid.step(Point::new(2, 3), Direction8::DownRight), None);
+        assert_eq!(values(grid.ray(Point::new(0, 0), Direction::Down)), "dgj");
+        assert_eq!(
+            values(grid.ray(Point::new(0, 3), Direction8::UpRight)),
+            "hf"
+        );
+        assert_eq!(values(grid.ray(Point::new(2, 1), Direction::Right)), "");
+    }
+
+    #[test]
+    fn neighbours_can_wrap_around() {
+        let grid = grid();
+
+        assert_eq!(
+            values(grid.neighbours_wrapping(Point::new(0, 0), Neighbourhood::Cardinal)),
+            "jcbd"
+        );
+        assert_eq!(
+            values(grid.neighbours_wrapping(Point::new(2, 3), Neighbourhood::Diagonal)),
+            "hgba"
+        );
+
+        let empty: Grid<u8> = Grid::new_with_value(0, 3, 0);
+        assert_eq!(empty.offset_point(Point::new(0, 1), (1, 0), true), None);
+        assert_eq!(
+            empty
+                .neighbour_points(Point::new(0, 1), Neighbourhood::Moore, true)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn neighbours_can_be_changed() {
+        let mut grid = grid();
+
+        grid.neighbours_mut(Point::new(0, 3), Neighbourhood::Moore, |_, v| {
+            *v = v.to_ascii_uppercase()
+        });
+        grid.neighbours_wrapping_mut(Point::new(2, 0), Neighbourhood::Cardinal, |p, v| {
+            if p.coords()[1] == 3 {
+                *v = b'#';
+            }
+        });
+
+        assert_eq!(grid.row(2), b"GHi");
+        assert_eq!(grid.row(3), b"jK#");
+    }
+}
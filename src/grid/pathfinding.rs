@@ -0,0 +1,364 @@
+use crate::geo::{Direction, Point};
+use crate::grid::{Grid, GridStorage};
+use crate::search::{dijkstra, DijkstraState, Search};
+use num::traits::SaturatingAdd;
+use num::{Bounded, FromPrimitive, Zero};
+use rustc_hash::FxHashMap;
+use std::collections::hash_map::Entry;
+use std::ops::Add;
+
+/// Where a path is at. The heading and run-length are only tracked if the path finder needs
+/// them, and they are `None` and 0 otherwise.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PathState {
+    pub pos: Point<usize>,
+    pub heading: Option<Direction>,
+    /// How many steps were taken in a straight line to get here.
+    pub run: u8,
+}
+
+/// A path found by `PathFinder`, including the start and goal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GridPath<C> {
+    pub cost: C,
+    pub steps: Vec<PathState>,
+}
+
+impl<C> GridPath<C> {
+    pub fn points(&self) -> impl Iterator<Item = Point<usize>> + '_ {
+        self.steps.iter().map(|s| s.pos)
+    }
+}
+
+/// Finds the cheapest path through a grid with dijkstra, or A* if a heuristic is enabled.
+///
+/// The cost function gets the state before and after the step along with the cell being
+/// stepped onto, and returns `None` if the step is not allowed.
+pub struct PathFinder<'g, T, S, CF> {
+    grid: &'g Grid<T, S>,
+    cost: CF,
+    track_heading: bool,
+    min_run: u8,
+    max_run: u8,
+    initial_heading: Option<Direction>,
+    min_step_cost: Option<usize>,
+}
+
+struct Step<C> {
+    state: PathState,
+    parent: Option<PathState>,
+    spent: C,
+    estimate: C,
+}
+
+impl<C> Clone for Step<C>
+where
+    C: Copy,
+{
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state,
+            parent: self.parent,
+            spent: self.spent,
+            estimate: self.estimate,
+        }
+    }
+}
+
+impl<C> DijkstraState<C, PathState> for Step<C>
+where
+    C: Ord + Eq + Copy,
+{
+    #[inline]
+    fn cost(&self) -> C {
+        self.estimate
+    }
+
+    #[inline]
+    fn key(&self) -> PathState {
+        self.state
+    }
+}
+
+impl<'g, T, S, C, CF> PathFinder<'g, T, S, CF>
+where
+    S: GridStorage<T>,
+    C: Copy + Ord + Zero + Add<Output = C> + FromPrimitive + Bounded + SaturatingAdd,
+    CF: Fn(&PathState, &PathState, &T) -> Option<C>,
+{
+    /// Track the heading in the state. The path can then turn, but never reverse.
+    pub fn with_heading(mut self) -> Self {
+        self.track_heading = true;
+        self
+    }
+
+    /// Limit how far the path can go in a straight line, and how far it has to go before it
+    /// can turn or stop at the goal. This also tracks the heading.
+    pub fn with_runs(mut self, min_run: u8, max_run: u8) -> Self {
+        self.track_heading = true;
+        self.min_run = min_run;
+        self.max_run = max_run;
+        self
+    }
+
+    /// Start facing this way. Without it, the first step can go in any direction.
+    pub fn facing(mut self, heading: Direction) -> Self {
+        self.track_heading = true;
+        self.initial_heading = Some(heading);
+        self
+    }
+
+    /// Use the Manhattan distance to the goal times the cheapest possible step as the A*
+    /// heuristic. A step cost that is too high can give the wrong path. Estimates that do not fit
+    /// in the cost type are capped at its largest value.
+    pub fn with_manhattan(mut self, min_step_cost: usize) -> Self {
+        self.min_step_cost = Some(min_step_cost);
+        self
+    }
+
+    pub fn find(&self, start: Point<usize>, goal: Point<usize>) -> Option<GridPath<C>> {
+        let [gx, gy] = *goal.coords();
+        let estimate = |p: Point<usize>| match self.min_step_cost {
+            Some(min_step_cost) => {
+                let [x, y] = *p.coords();
+                let distance = x.abs_diff(gx) + y.abs_diff(gy);
+                distance
+                    .checked_mul(min_step_cost)
+                    .and_then(C::from_usize)
+                    .unwrap_or_else(C::max_value)
+            }
+            None => C::zero(),
+        };
+
+        let initial = PathState {
+            pos: start,
+            heading: self.initial_heading,
+            run: 0,
+        };
+        // The search keeps track of the best cost of each state, so only the parents of the
+        // states are needed here. They are added when a state is first visited, which is when
+        // it has its lowest cost.
+        let mut parents: FxHashMap<PathState, Option<PathState>> = FxHashMap::default();
+
+        let mut search = dijkstra();
+        search.reset(Step {
+            state: initial,
+            parent: None,
+            spent: C::zero(),
+            estimate: estimate(start),
+        });
+
+        while let Some(Step {
+            state,
+            parent,
+            spent: cost,
+            ..
+        }) = search.next_state()
+        {
+            match parents.entry(state) {
+                Entry::Occupied(_) => continue,
+                Entry::Vacant(entry) => {
+                    entry.insert(parent);
+                }
+            }
+            if state.pos == goal && state.run >= self.min_run {
+                return Some(self.rebuild(&parents, state, cost));
+            }
+
+            for dir in Direction::ALL {
+                let next = match self.next_state(&state, dir) {
+                    Some(next) => next,
+                    None => continue,
+                };
+                let step_cost = match (self.cost)(&state, &next, &self.grid[next.pos]) {
+                    Some(step_cost) => step_cost,
+                    None => continue,
+                };
+
+                let next_cost = cost + step_cost;
+                search.add_state(Step {
+                    state: next,
+                    parent: Some(state),
+                    spent: next_cost,
+                    estimate: next_cost.saturating_add(&estimate(next.pos)),
+                });
+            }
+        }
+
+        None
+    }
+
+    fn next_state(&self, state: &PathState, dir: Direction) -> Option<PathState> {
+        let pos = self.grid.step(state.pos, dir)?;
+        if !self.track_heading {
+            return Some(PathState {
+                pos,
+                heading: None,
+                run: 0,
+            });
+        }
+
+        let run = match state.heading {
+            Some(heading) if heading == dir => {
+                if self.max_run > 0 && state.run >= self.max_run {
+                    return None;
+                }
+                state.run + 1
+            }
+            Some(heading) if heading == dir.reverse() => return None,
+            Some(_) if state.run < self.min_run && state.run > 0 => return None,
+            _ => 1,
+        };
+
+        Some(PathState {
+            pos,
+            heading: Some(dir),
+            run,
+        })
+    }
+
+    fn rebuild(
+        &self,
+        parents: &FxHashMap<PathState, Option<PathState>>,
+        goal: PathState,
+        cost: C,
+    ) -> GridPath<C> {
+        let mut steps = vec![goal];
+        while let Some(prev) = parents[steps.last().unwrap()] {
+            steps.push(prev);
+        }
+        steps.reverse();
+
+        GridPath { cost, steps }
+    }
+}
+
+impl<T, S> Grid<T, S>
+where
+    S: GridStorage<T>,
+{
+    pub fn path_finder<C, CF>(&self, cost: CF) -> PathFinder<'_, T, S, CF>
+    where
+        CF: Fn(&PathState, &PathState, &T) -> Option<C>,
+    {
+        PathFinder {
+            grid: self,
+            cost,
+            track_heading: false,
+            min_run: 0,
+            max_run: 0,
+            initial_heading: None,
+            min_step_cost: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    fn grid(input: &[u8]) -> Grid<u8> {
+        Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(input)
+            .unwrap()
+    }
+
+    fn walk(_: &PathState, _: &PathState, v: &u8) -> Option<u32> {
+        if *v == b'#' {
+            None
+        } else {
+            Some(1)
+        }
+    }
+
+    #[test]
+    fn path_goes_around_walls() {
+        let grid = grid(b"S..#....\n.#.#.##.\n.#...#..\n.####.#.\n......#E\n");
+        let start = grid.find(&b'S').unwrap();
+        let goal = grid.find(&b'E').unwrap();
+
+        let path = grid.path_finder(walk).find(start, goal).unwrap();
+        assert_eq!(path.cost, 15);
+        assert_eq!(path.steps.len(), 16);
+        assert_eq!(path.steps[0].pos, start);
+        assert_eq!(path.steps[15].pos, goal);
+        assert!(path.points().zip(path.points().skip(1)).all(|(a, b)| {
+            let ([ax, ay], [bx, by]) = (*a.coords(), *b.coords());
+            ax.abs_diff(bx) + ay.abs_diff(by) == 1
+        }));
+
+        let path = grid
+            .path_finder(walk)
+            .with_manhattan(1)
+            .find(start, goal)
+            .unwrap();
+        assert_eq!(path.cost, 15);
+
+        assert_eq!(grid.path_finder(walk).find(start, Point::new(3, 0)), None);
+    }
+
+    #[test]
+    fn path_estimates_saturate() {
+        let mut grid: Grid<u8> = Grid::new_with_value(300, 3, b'.');
+        grid[(2, 0)] = b'#';
+        grid[(2, 1)] = b'#';
+        grid[(0, 2)] = b'#';
+        grid[(1, 2)] = b'#';
+        let narrow = |_: &PathState, _: &PathState, v: &u8| (*v != b'#').then_some(1u8);
+
+        let finder = grid.path_finder(narrow).with_manhattan(1);
+        assert_eq!(finder.find(Point::new(0, 0), Point::new(299, 2)), None);
+        assert_eq!(
+            finder
+                .find(Point::new(0, 0), Point::new(1, 1))
+                .map(|p| p.cost),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn path_can_limit_straight_runs() {
+        let grid = grid(b"11111\n99991\n99991\n99991\n99991\n");
+        let cost = |_: &PathState, _: &PathState, v: &u8| Some((*v - b'0') as u32);
+        let (start, goal) = (Point::new(0, 0), Point::new(4, 4));
+
+        assert_eq!(grid.path_finder(cost).find(start, goal).unwrap().cost, 8);
+
+        let path = grid
+            .path_finder(cost)
+            .with_runs(1, 3)
+            .find(start, goal)
+            .unwrap();
+        assert_eq!(path.cost, 16);
+        assert!(path.steps.iter().all(|s| s.run <= 3));
+        assert_eq!(path.steps.last().unwrap().heading, Some(Direction::Down));
+
+        let path = grid
+            .path_finder(cost)
+            .with_runs(4, 10)
+            .find(start, goal)
+            .unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.steps.len(), 9);
+    }
+
+    #[test]
+    fn path_can_price_turns() {
+        let grid = grid(b"....\n.##.\n....\n");
+        let cost = |from: &PathState, to: &PathState, v: &u8| match (*v, from.heading) {
+            (b'#', _) => None,
+            (_, Some(heading)) if Some(heading) != to.heading => Some(1001),
+            _ => Some(1),
+        };
+
+        let path = grid
+            .path_finder(cost)
+            .facing(Direction::Right)
+            .find(Point::new(0, 2), Point::new(3, 0))
+            .unwrap();
+
+        assert_eq!(path.cost, 1005);
+        assert_eq!(path.steps[3].pos, Point::new(3, 2));
+    }
+}
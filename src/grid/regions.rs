@@ -0,0 +1,200 @@
+use crate::geo::Point;
+use crate::grid::{Grid, GridStorage, Neighbourhood};
+use crate::search::{bfs, Search};
+
+/// A connected group of cells found by `Grid::label_components`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Component {
+    pub size: usize,
+    /// The number of cell edges that do not border another cell in the component, including
+    /// the ones along the edge of the grid.
+    pub perimeter: usize,
+    pub top_left: Point<usize>,
+    pub bottom_right: Point<usize>,
+}
+
+/// The result of `Grid::label_components`. The label of a cell is the index of its component.
+#[derive(Clone, Debug)]
+pub struct Components {
+    pub labels: Grid<usize, Vec<usize>>,
+    pub components: Vec<Component>,
+}
+
+impl Components {
+    #[inline]
+    pub fn label(&self, p: Point<usize>) -> usize {
+        self.labels[p]
+    }
+
+    #[inline]
+    pub fn component_at(&self, p: Point<usize>) -> &Component {
+        &self.components[self.labels[p]]
+    }
+}
+
+impl<T, S> Grid<T, S>
+where
+    S: GridStorage<T>,
+{
+    /// Find the cells that can be reached from `start` through cardinal steps on passable
+    /// cells, in the order they were reached. It is empty if `start` is not passable.
+    pub fn flood_fill<F>(&self, start: Point<usize>, passable: F) -> Vec<Point<usize>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        bfs().with_initial_state(start).gather(|s, p| {
+            if !passable(&self[p]) {
+                return None;
+            }
+
+            for (next, v) in self.neighbours(p, Neighbourhood::Cardinal) {
+                if passable(v) {
+                    s.add_state(next);
+                }
+            }
+
+            Some(p)
+        })
+    }
+
+    /// Split the grid into components of cells connected by cardinal steps, where two
+    /// neighbours are connected if `eq` returns true for them. The components are labelled in
+    /// the order their first cell appears in reading order. If `eq` is not symmetric, a cell
+    /// belongs to the first component that reaches it.
+    pub fn label_components<F>(&self, eq: F) -> Components
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        const UNLABELLED: usize = usize::MAX;
+
+        let mut labels = Grid::new_with_value(self.width, self.height, UNLABELLED);
+        let mut components = Vec::new();
+        let mut search = bfs();
+
+        for (start, _) in self.iter() {
+            if labels[start] != UNLABELLED {
+                continue;
+            }
+
+            let label = components.len();
+            let mut component = Component {
+                size: 0,
+                perimeter: 0,
+                top_left: start,
+                bottom_right: start,
+            };
+
+            search.reset(start);
+            while let Some(p) = search.next_state() {
+                labels[p] = label;
+
+                let [x, y] = *p.coords();
+                let [left, top] = *component.top_left.coords();
+                let [right, bottom] = *component.bottom_right.coords();
+                component.top_left = Point::new(left.min(x), top.min(y));
+                component.bottom_right = Point::new(right.max(x), bottom.max(y));
+                component.size += 1;
+                component.perimeter += 4;
+
+                for (next, v) in self.neighbours(p, Neighbourhood::Cardinal) {
+                    if (labels[next] == UNLABELLED || labels[next] == label) && eq(&self[p], v) {
+                        component.perimeter -= 1;
+                        search.add_state(next);
+                    }
+                }
+            }
+
+            components.push(component);
+        }
+
+        Components { labels, components }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    const GARDEN: &[u8] = b"AAAA
+BBCD
+BBCC
+EEEC
+";
+
+    fn grid(input: &[u8]) -> Grid<u8> {
+        Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(input)
+            .unwrap()
+    }
+
+    #[test]
+    fn flood_fill_stays_within_passable_cells() {
+        let grid = grid(b"..#..\n.##..\n#...#\n");
+
+        let filled = grid.flood_fill(Point::new(0, 0), |v| *v == b'.');
+        assert_eq!(
+            filled,
+            vec![Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)]
+        );
+
+        let filled = grid.flood_fill(Point::new(4, 0), |v| *v == b'.');
+        assert_eq!(filled.len(), 7);
+        assert!(filled.contains(&Point::new(1, 2)));
+
+        assert!(grid.flood_fill(Point::new(2, 0), |v| *v == b'.').is_empty());
+    }
+
+    #[test]
+    fn components_are_labelled() {
+        let grid = grid(GARDEN);
+        let res = grid.label_components(|a, b| a == b);
+
+        assert_eq!(res.components.len(), 5);
+        assert_eq!(res.label(Point::new(3, 0)), 0);
+        assert_eq!(res.label(Point::new(1, 2)), 1);
+        assert_eq!(res.label(Point::new(3, 3)), 2);
+        assert_eq!(res.label(Point::new(3, 1)), 3);
+        assert_eq!(res.label(Point::new(0, 3)), 4);
+
+        assert_eq!(
+            res.components
+                .iter()
+                .map(|c| (c.size, c.perimeter))
+                .collect::<Vec<_>>(),
+            vec![(4, 10), (4, 8), (4, 10), (1, 4), (3, 8)]
+        );
+        assert_eq!(
+            res.components
+                .iter()
+                .map(|c| c.size * c.perimeter)
+                .sum::<usize>(),
+            140
+        );
+
+        let c = res.component_at(Point::new(2, 2));
+        assert_eq!(c.top_left, Point::new(2, 1));
+        assert_eq!(c.bottom_right, Point::new(3, 3));
+    }
+
+    #[test]
+    fn components_do_not_take_labelled_cells() {
+        let grid = grid(b"BAB\nCAA\n");
+        let res = grid.label_components(|a, b| a <= b);
+
+        assert_eq!(res.components.len(), 2);
+        assert_eq!(res.label(Point::new(0, 0)), 0);
+        assert_eq!(res.label(Point::new(0, 1)), 0);
+        for p in [(1, 0), (2, 0), (1, 1), (2, 1)] {
+            assert_eq!(res.label(Point::from(p)), 1);
+        }
+
+        assert_eq!(
+            res.components
+                .iter()
+                .map(|c| (c.size, c.perimeter))
+                .collect::<Vec<_>>(),
+            vec![(2, 7), (4, 10)]
+        );
+    }
+}
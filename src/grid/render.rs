@@ -0,0 +1,247 @@
+use crate::geo::Point;
+use crate::grid::{Grid, GridStorage};
+use rustc_hash::FxHashSet;
+use std::fmt::{Display, Formatter, Write};
+
+/// A cell that has an obvious character to show it as.
+pub trait Glyph {
+    fn glyph(&self) -> char;
+}
+
+impl Glyph for u8 {
+    #[inline]
+    fn glyph(&self) -> char {
+        *self as char
+    }
+}
+
+impl Glyph for char {
+    #[inline]
+    fn glyph(&self) -> char {
+        *self
+    }
+}
+
+impl Glyph for bool {
+    #[inline]
+    fn glyph(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Grey,
+}
+
+impl Colour {
+    #[inline]
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Colour::Red => 91,
+            Colour::Green => 92,
+            Colour::Yellow => 93,
+            Colour::Blue => 94,
+            Colour::Magenta => 95,
+            Colour::Cyan => 96,
+            Colour::White => 97,
+            Colour::Grey => 90,
+        }
+    }
+}
+
+struct Overlay {
+    points: FxHashSet<Point<usize>>,
+    colour: Colour,
+    glyph: Option<char>,
+}
+
+/// Renders a grid as text, one line per row. Overlays highlight sets of points with a colour,
+/// and can replace their glyphs too. Where overlays overlap, the last one added wins.
+///
+/// In plain mode, no ANSI escapes are written, but the overlay glyphs are still used. This is
+/// what snapshot tests should compare against.
+pub struct Renderer<'g, T, S, F> {
+    grid: &'g Grid<T, S>,
+    glyph: F,
+    overlays: Vec<Overlay>,
+    plain: bool,
+}
+
+impl<'g, T, S, F> Renderer<'g, T, S, F>
+where
+    S: GridStorage<T>,
+    F: Fn(&T) -> char,
+{
+    pub fn overlay<I>(self, points: I, colour: Colour) -> Self
+    where
+        I: IntoIterator<Item = Point<usize>>,
+    {
+        self.add_overlay(points, colour, None)
+    }
+
+    pub fn overlay_with_glyph<I>(self, points: I, colour: Colour, glyph: char) -> Self
+    where
+        I: IntoIterator<Item = Point<usize>>,
+    {
+        self.add_overlay(points, colour, Some(glyph))
+    }
+
+    pub fn plain(mut self) -> Self {
+        self.plain = true;
+        self
+    }
+
+    fn add_overlay<I>(mut self, points: I, colour: Colour, glyph: Option<char>) -> Self
+    where
+        I: IntoIterator<Item = Point<usize>>,
+    {
+        self.overlays.push(Overlay {
+            points: points.into_iter().collect(),
+            colour,
+            glyph,
+        });
+        self
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl<'g, T, S, F> Display for Renderer<'g, T, S, F>
+where
+    S: GridStorage<T>,
+    F: Fn(&T) -> char,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.grid.rows() {
+            if y > 0 {
+                f.write_char('\n')?;
+            }
+
+            let mut current: Option<Colour> = None;
+            for (x, v) in row.iter().enumerate() {
+                let p = Point::new(x, y);
+                let overlay = self.overlays.iter().rev().find(|o| o.points.contains(&p));
+                let glyph = overlay
+                    .and_then(|o| o.glyph)
+                    .unwrap_or_else(|| (self.glyph)(v));
+
+                if !self.plain {
+                    let colour = overlay.map(|o| o.colour);
+                    if colour != current {
+                        match colour {
+                            Some(colour) => write!(f, "\x1b[{}m", colour.ansi_code())?,
+                            None => f.write_str("\x1b[0m")?,
+                        }
+                        current = colour;
+                    }
+                }
+
+                f.write_char(glyph)?;
+            }
+
+            if current.is_some() {
+                f.write_str("\x1b[0m")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, S> Grid<T, S>
+where
+    S: GridStorage<T>,
+{
+    #[inline]
+    pub fn renderer<F>(&self, glyph: F) -> Renderer<'_, T, S, F>
+    where
+        F: Fn(&T) -> char,
+    {
+        Renderer {
+            grid: self,
+            glyph,
+            overlays: Vec::new(),
+            plain: false,
+        }
+    }
+}
+
+impl<T, S> Display for Grid<T, S>
+where
+    S: GridStorage<T>,
+    T: Glyph,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.renderer(T::glyph).plain().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    fn grid() -> Grid<u8> {
+        Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(b"#...\n.##.\n...#\n")
+            .unwrap()
+    }
+
+    #[test]
+    fn grid_can_be_displayed() {
+        let grid = grid();
+        assert_eq!(grid.to_string(), "#...\n.##.\n...#");
+
+        let bools: Grid<bool> = Grid::new_with_value(3, 2, false);
+        assert_eq!(bools.to_string(), "...\n...");
+
+        assert_eq!(
+            grid.renderer(|v| if *v == b'#' { '█' } else { ' ' })
+                .render(),
+            "█   \n ██ \n   █"
+        );
+    }
+
+    #[test]
+    fn overlays_replace_glyphs_in_plain_mode() {
+        let grid = grid();
+        let path = [Point::new(1, 0), Point::new(2, 0), Point::new(3, 0)];
+
+        assert_eq!(
+            grid.renderer(|v| *v as char)
+                .overlay_with_glyph(path, Colour::Red, 'O')
+                .overlay_with_glyph([Point::new(3, 0)], Colour::Green, 'E')
+                .overlay([Point::new(0, 2)], Colour::Blue)
+                .plain()
+                .render(),
+            "#OOE\n.##.\n...#"
+        );
+    }
+
+    #[test]
+    fn overlays_are_coloured() {
+        let grid = grid();
+
+        assert_eq!(
+            grid.renderer(|v| *v as char)
+                .overlay([Point::new(1, 1), Point::new(2, 1)], Colour::Red)
+                .overlay([Point::new(3, 2)], Colour::Green)
+                .render(),
+            "#...\n.\x1b[91m##\x1b[0m.\n...\x1b[92m#\x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use crate::geo::Point;
+use crate::grid::Grid;
+use rustc_hash::FxHashMap;
+use std::ops::{Index, IndexMut};
+
+/// A grid that only stores the cells that have been set, for when few cells are used over a
+/// huge or unbounded coordinate space. The cells that are not set have the default value.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: FxHashMap<Point<i64>, T>,
+    default: T,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            cells: FxHashMap::default(),
+            default,
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, p: Point<i64>) -> &T {
+        self.cells.get(&p).unwrap_or(&self.default)
+    }
+
+    #[inline]
+    pub fn insert(&mut self, p: Point<i64>, v: T) -> Option<T> {
+        self.cells.insert(p, v)
+    }
+
+    /// Unset the cell, so it will have the default value again.
+    #[inline]
+    pub fn remove(&mut self, p: Point<i64>) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    #[inline]
+    pub fn contains(&self, p: Point<i64>) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// The number of cells that are set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.cells.clear()
+    }
+
+    /// Iterate over the set cells in no particular order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Point<i64>, &T)> {
+        self.cells.iter().map(|(p, v)| (*p, v))
+    }
+
+    #[inline]
+    pub fn count_by<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.cells.values().filter(|v| pred(v)).count()
+    }
+
+    /// The top left and bottom right corners of the set cells, inclusive.
+    pub fn bounding_box(&self) -> Option<(Point<i64>, Point<i64>)> {
+        let mut iter = self.cells.keys();
+        let first = *iter.next()?;
+
+        Some(iter.fold((first, first), |(tl, br), p| {
+            let [x, y] = *p.coords();
+            (
+                Point::new(tl.coords()[0].min(x), tl.coords()[1].min(y)),
+                Point::new(br.coords()[0].max(x), br.coords()[1].max(y)),
+            )
+        }))
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Eq,
+{
+    /// Find the first set cell with this value in reading order.
+    pub fn find(&self, needle: &T) -> Option<Point<i64>> {
+        self.cells
+            .iter()
+            .filter(|(_, v)| *v == needle)
+            .map(|(p, _)| *p)
+            .min_by_key(|p| {
+                let [x, y] = *p.coords();
+                (y, x)
+            })
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Copy,
+{
+    /// Copy the bounding box into a dense grid, where the cells that are not set are filled
+    /// with `fill`. The top left corner of the box is returned with it, so `p - top_left` gives
+    /// the position in the dense grid.
+    pub fn to_grid(&self, fill: T) -> (Grid<T, Vec<T>>, Point<i64>) {
+        let (tl, br) = match self.bounding_box() {
+            Some(bounds) => bounds,
+            None => return (Grid::new_with_value(0, 0, fill), Point::new(0, 0)),
+        };
+        let [width, height] = *(br - tl + Point::new(1, 1)).coords();
+
+        let mut grid = Grid::new_with_value(width as usize, height as usize, fill);
+        for (p, v) in self.cells.iter() {
+            let [x, y] = *(*p - tl).coords();
+            grid[(x as usize, y as usize)] = *v;
+        }
+
+        (grid, tl)
+    }
+
+    /// Render the bounding box as text with one line per row, using the glyph function for both
+    /// the set cells and the default ones.
+    pub fn render<F>(&self, glyph: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let (tl, br) = match self.bounding_box() {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+        let ([left, top], [right, bottom]) = (*tl.coords(), *br.coords());
+
+        let mut res = String::with_capacity(((right - left + 2) * (bottom - top + 1)) as usize);
+        for y in top..=bottom {
+            if y != top {
+                res.push('\n');
+            }
+
+            for x in left..=right {
+                res.push(glyph(self.get(Point::new(x, y))));
+            }
+        }
+
+        res
+    }
+}
+
+impl<T> FromIterator<(Point<i64>, T)> for SparseGrid<T>
+where
+    T: Default,
+{
+    fn from_iter<I: IntoIterator<Item = (Point<i64>, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+            default: T::default(),
+        }
+    }
+}
+
+impl<T> Index<Point<i64>> for SparseGrid<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: Point<i64>) -> &Self::Output {
+        self.get(index)
+    }
+}
+
+impl<T> IndexMut<Point<i64>> for SparseGrid<T>
+where
+    T: Clone,
+{
+    /// Get the cell for writing. Unset cells are set to the default value first.
+    #[inline]
+    fn index_mut(&mut self, index: Point<i64>) -> &mut Self::Output {
+        self.cells
+            .entry(index)
+            .or_insert_with(|| self.default.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparse_grid_stores_set_cells() {
+        let mut grid = SparseGrid::new(b'.');
+
+        assert_eq!(grid[Point::new(1_000_000, -5)], b'.');
+        assert_eq!(grid.bounding_box(), None);
+        assert_eq!(grid.render(|v| *v as char), "");
+
+        grid[Point::new(-2, 1)] = b'#';
+        grid[Point::new(1, -1)] = b'#';
+        grid.insert(Point::new(0, 1), b'S');
+
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid[Point::new(0, 1)], b'S');
+        assert!(!grid.contains(Point::new(0, 0)));
+        assert_eq!(grid.find(&b'#'), Some(Point::new(1, -1)));
+        assert_eq!(grid.find(&b'.'), None);
+        assert_eq!(grid.count_by(|v| *v == b'#'), 2);
+        assert_eq!(
+            grid.bounding_box(),
+            Some((Point::new(-2, -1), Point::new(1, 1)))
+        );
+        assert_eq!(grid.render(|v| *v as char), "...#\n....\n#.S.");
+
+        let (dense, top_left) = grid.to_grid(b' ');
+        assert_eq!(top_left, Point::new(-2, -1));
+        assert_eq!(dense.width(), 4);
+        assert_eq!(dense.height(), 3);
+        assert_eq!(dense[(2, 2)], b'S');
+        assert_eq!(dense[(0, 0)], b' ');
+
+        grid.remove(Point::new(1, -1));
+        assert_eq!(grid[Point::new(1, -1)], b'.');
+        assert_eq!(grid.render(|v| *v as char), "#.S");
+    }
+
+    #[test]
+    fn sparse_grid_collects_from_points() {
+        let grid: SparseGrid<bool> = (0..5).map(|i| (Point::new(i, i * 2), true)).collect();
+
+        assert_eq!(grid.len(), 5);
+        assert!(grid[Point::new(4, 8)]);
+        assert!(!grid[Point::new(4, 7)]);
+        assert_eq!(grid.iter().filter(|(p, _)| p.coords()[0] > 2).count(), 2);
+    }
+}
@@ -0,0 +1,490 @@
+use crate::geo::Point;
+use crate::grid::{Grid, GridStorage};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+/// One of the eight ways to turn and mirror a grid. The rotations are clockwise.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror left to right.
+    FlipHorizontal,
+    /// Mirror top to bottom.
+    FlipVertical,
+    /// Mirror along the top-left to bottom-right diagonal.
+    Transpose,
+    /// Mirror along the top-right to bottom-left diagonal.
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// Whether the width and height are swapped by this transform.
+    #[inline]
+    pub fn swaps_axes(&self) -> bool {
+        matches!(
+            self,
+            Transform::Rotate90
+                | Transform::Rotate270
+                | Transform::Transpose
+                | Transform::AntiTranspose
+        )
+    }
+
+    /// The size of the result of transforming a grid of this size.
+    #[inline]
+    pub fn size(&self, width: usize, height: usize) -> (usize, usize) {
+        if self.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Map a position in the transformed grid to the position in the source grid, which has the
+    /// given size.
+    #[inline]
+    pub fn source(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (y, height - 1 - x),
+            Transform::Rotate180 => (width - 1 - x, height - 1 - y),
+            Transform::Rotate270 => (width - 1 - y, x),
+            Transform::FlipHorizontal => (width - 1 - x, y),
+            Transform::FlipVertical => (x, height - 1 - y),
+            Transform::Transpose => (y, x),
+            Transform::AntiTranspose => (width - 1 - y, height - 1 - x),
+        }
+    }
+}
+
+/// The read API shared by grids and their views, so the same algorithm can run on a grid in
+/// any orientation.
+pub trait GridRead<T> {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn get(&self, x: usize, y: usize) -> &T;
+
+    #[inline]
+    fn row_iter<'a>(&'a self, y: usize) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        (0..self.width()).map(move |x| self.get(x, y))
+    }
+
+    #[inline]
+    fn column_iter<'a>(&'a self, x: usize) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        (0..self.height()).map(move |y| self.get(x, y))
+    }
+
+    #[inline]
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Point<usize>, &'a T)>
+    where
+        T: 'a,
+    {
+        let width = self.width();
+        (0..width * self.height()).map(move |i| {
+            let (x, y) = (i % width, i / width);
+            (Point::new(x, y), self.get(x, y))
+        })
+    }
+
+    #[inline]
+    fn count_by<F>(&self, pred: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.iter().filter(|(_, v)| pred(v)).count()
+    }
+
+    #[inline]
+    fn find(&self, needle: &T) -> Option<Point<usize>>
+    where
+        T: Eq,
+    {
+        self.iter().find(|(_, v)| *v == needle).map(|(p, _)| p)
+    }
+
+    #[inline]
+    fn rows_equal(&self, a: usize, b: usize) -> bool
+    where
+        T: Eq,
+    {
+        self.row_iter(a).eq(self.row_iter(b))
+    }
+
+    #[inline]
+    fn columns_equal(&self, a: usize, b: usize) -> bool
+    where
+        T: Eq,
+    {
+        self.column_iter(a).eq(self.column_iter(b))
+    }
+
+    /// Look at this through a transform without copying it.
+    #[inline]
+    fn view(&self, transform: Transform) -> GridView<'_, Self, T>
+    where
+        Self: Sized,
+    {
+        GridView {
+            grid: self,
+            transform,
+            spooky_ghost: Default::default(),
+        }
+    }
+
+    /// Copy what is seen into a new grid.
+    fn to_grid(&self) -> Grid<T, Vec<T>>
+    where
+        T: Copy + Default,
+    {
+        let mut storage = Vec::with_capacity(self.width() * self.height());
+        for y in 0..self.height() {
+            storage.extend(self.row_iter(y).copied());
+        }
+
+        Grid::new_from_storage(self.width(), self.height(), storage)
+    }
+}
+
+impl<T, S> GridRead<T> for Grid<T, S>
+where
+    S: GridStorage<T>,
+{
+    #[inline]
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        Grid::height(self)
+    }
+
+    #[inline]
+    fn get(&self, x: usize, y: usize) -> &T {
+        &self[(x, y)]
+    }
+}
+
+/// A transformed read-only view of a grid, or of another view.
+pub struct GridView<'g, G, T> {
+    grid: &'g G,
+    transform: Transform,
+    spooky_ghost: PhantomData<T>,
+}
+
+impl<'g, G, T> Copy for GridView<'g, G, T> {}
+
+impl<'g, G, T> Clone for GridView<'g, G, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'g, G, T> GridView<'g, G, T> {
+    #[inline]
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+}
+
+impl<'g, G, T> GridRead<T> for GridView<'g, G, T>
+where
+    G: GridRead<T>,
+{
+    #[inline]
+    fn width(&self) -> usize {
+        self.transform.size(self.grid.width(), self.grid.height()).0
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.transform.size(self.grid.width(), self.grid.height()).1
+    }
+
+    #[inline]
+    fn get(&self, x: usize, y: usize) -> &T {
+        let (sx, sy) = self
+            .transform
+            .source(x, y, self.grid.width(), self.grid.height());
+        self.grid.get(sx, sy)
+    }
+}
+
+impl<'g, G, T> Index<(usize, usize)> for GridView<'g, G, T>
+where
+    G: GridRead<T>,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y)
+    }
+}
+
+impl<'g, G, T> Index<Point<usize>> for GridView<'g, G, T>
+where
+    G: GridRead<T>,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, p: Point<usize>) -> &T {
+        let [x, y] = *p.coords();
+        self.get(x, y)
+    }
+}
+
+/// A transformed view of a grid that can also write to the cells.
+pub struct GridViewMut<'g, T, S> {
+    grid: &'g mut Grid<T, S>,
+    transform: Transform,
+}
+
+impl<'g, T, S> GridViewMut<'g, T, S>
+where
+    S: GridStorage<T>,
+{
+    #[inline]
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
+        let (sx, sy) = self
+            .transform
+            .source(x, y, self.grid.width(), self.grid.height());
+        &mut self.grid[(sx, sy)]
+    }
+}
+
+impl<'g, T, S> GridRead<T> for GridViewMut<'g, T, S>
+where
+    S: GridStorage<T>,
+{
+    #[inline]
+    fn width(&self) -> usize {
+        self.transform.size(self.grid.width(), self.grid.height()).0
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.transform.size(self.grid.width(), self.grid.height()).1
+    }
+
+    #[inline]
+    fn get(&self, x: usize, y: usize) -> &T {
+        let (sx, sy) = self
+            .transform
+            .source(x, y, self.grid.width(), self.grid.height());
+        &self.grid[(sx, sy)]
+    }
+}
+
+impl<'g, T, S> Index<(usize, usize)> for GridViewMut<'g, T, S>
+where
+    S: GridStorage<T>,
+{
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y)
+    }
+}
+
+impl<'g, T, S> IndexMut<(usize, usize)> for GridViewMut<'g, T, S>
+where
+    S: GridStorage<T>,
+{
+    #[inline]
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        self.get_mut(x, y)
+    }
+}
+
+impl<T, S> Grid<T, S>
+where
+    S: GridStorage<T>,
+{
+    /// Look at and write to the grid through a transform without copying it.
+    #[inline]
+    pub fn view_mut(&mut self, transform: Transform) -> GridViewMut<'_, T, S> {
+        GridViewMut {
+            grid: self,
+            transform,
+        }
+    }
+}
+
+impl<T, S> Grid<T, S>
+where
+    S: GridStorage<T>,
+    T: Copy,
+{
+    /// Get a transformed copy of the grid.
+    pub fn transformed(&self, transform: Transform) -> Grid<T, S> {
+        let (width, height) = transform.size(self.width, self.height);
+        let mut storage = S::create(width * height, self.default);
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = transform.source(x, y, self.width, self.height);
+                storage[(y * width) + x] = self[(sx, sy)];
+            }
+        }
+
+        Grid::new_from_storage_and_default(width, height, storage, self.default)
+    }
+
+    /// Transform the grid in place. Mirroring and half turns swap cells without allocating,
+    /// while the rest need a copy unless the grid is square.
+    pub fn apply_transform(&mut self, transform: Transform) {
+        let (width, height) = (self.width, self.height);
+
+        match transform {
+            Transform::Identity => {}
+            Transform::FlipHorizontal => {
+                for y in 0..height {
+                    self.storage
+                        .cell_range_mut(y * width, (y + 1) * width)
+                        .reverse();
+                }
+            }
+            Transform::FlipVertical => {
+                for y in 0..height / 2 {
+                    for x in 0..width {
+                        let a = (y * width) + x;
+                        let b = ((height - 1 - y) * width) + x;
+                        let tmp = self.storage[a];
+                        self.storage[a] = self.storage[b];
+                        self.storage[b] = tmp;
+                    }
+                }
+            }
+            Transform::Rotate180 => {
+                self.storage.cell_range_mut(0, width * height).reverse();
+            }
+            Transform::Transpose if width == height => {
+                for y in 0..height {
+                    for x in (y + 1)..width {
+                        let a = (y * width) + x;
+                        let b = (x * width) + y;
+                        let tmp = self.storage[a];
+                        self.storage[a] = self.storage[b];
+                        self.storage[b] = tmp;
+                    }
+                }
+            }
+            Transform::Rotate90 if width == height => {
+                self.apply_transform(Transform::Transpose);
+                self.apply_transform(Transform::FlipHorizontal);
+            }
+            Transform::Rotate270 if width == height => {
+                self.apply_transform(Transform::Transpose);
+                self.apply_transform(Transform::FlipVertical);
+            }
+            _ => {
+                *self = self.transformed(transform);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, Parser};
+
+    fn grid(input: &[u8]) -> Grid<u8> {
+        Grid::parser(any_byte().only_if(|v| *v != b'\n'))
+            .parse(input)
+            .unwrap()
+    }
+
+    #[test]
+    fn views_match_transformed_copies() {
+        let g = grid(b"abc\ndef\n");
+
+        let expected: [(Transform, &[u8]); 8] = [
+            (Transform::Identity, b"abc\ndef\n"),
+            (Transform::Rotate90, b"da\neb\nfc\n"),
+            (Transform::Rotate180, b"fed\ncba\n"),
+            (Transform::Rotate270, b"cf\nbe\nad\n"),
+            (Transform::FlipHorizontal, b"cba\nfed\n"),
+            (Transform::FlipVertical, b"def\nabc\n"),
+            (Transform::Transpose, b"ad\nbe\ncf\n"),
+            (Transform::AntiTranspose, b"fc\neb\nda\n"),
+        ];
+
+        for (transform, expected) in expected {
+            let expected = grid(expected);
+            let view = g.view(transform);
+
+            assert_eq!(view.to_grid(), expected, "{:?}", transform);
+            assert_eq!(g.transformed(transform), expected, "{:?}", transform);
+            assert_eq!(view[(0, 1)], expected[(0, 1)], "{:?}", transform);
+
+            let mut in_place = g.clone();
+            in_place.apply_transform(transform);
+            assert_eq!(in_place, expected, "{:?}", transform);
+        }
+
+        assert_eq!(
+            g.view(Transform::Rotate90)
+                .view(Transform::Rotate90)
+                .to_grid(),
+            grid(b"fed\ncba\n")
+        );
+    }
+
+    #[test]
+    fn square_grids_transform_in_place() {
+        let g = grid(b"abc\ndef\nghi\n");
+
+        for transform in Transform::ALL {
+            let mut in_place = g.clone();
+            in_place.apply_transform(transform);
+            assert_eq!(in_place, g.transformed(transform), "{:?}", transform);
+        }
+    }
+
+    #[test]
+    fn views_share_the_read_api() {
+        let g = grid(b"#..\n#.#\n...\n");
+        let view = g.view(Transform::Transpose);
+
+        assert!(view.rows_equal(0, 0));
+        assert!(!g.view(Transform::Identity).rows_equal(0, 1));
+        assert!(g.view(Transform::Identity).columns_equal(1, 1));
+        assert_eq!(view.find(&b'#'), Some(Point::new(0, 0)));
+        assert_eq!(view.row_iter(0).copied().collect::<Vec<_>>(), b"##.");
+        assert_eq!(view.count_by(|v| *v == b'#'), 3);
+
+        let mut g = g;
+        let mut north_is_west = g.view_mut(Transform::Transpose);
+        north_is_west[(2, 0)] = b'O';
+        assert_eq!(g[(0, 2)], b'O');
+    }
+}
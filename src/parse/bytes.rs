@@ -1,4 +1,5 @@
 use super::{ParseResult, Parser};
+use crate::utils::scan::{find_byte, find_either_byte};
 
 #[derive(Copy, Clone)]
 struct Everything;
@@ -63,7 +64,7 @@ struct BytesUntil(u8, bool);
 impl<'i> Parser<'i, &'i [u8]> for BytesUntil {
     #[inline]
     fn parse(&self, input: &'i [u8]) -> ParseResult<'i, &'i [u8]> {
-        match input.iter().position(|v| *v == self.0) {
+        match find_byte(input, self.0) {
             Some(pos) => ParseResult::Good(&input[..pos], &input[pos + (self.1 as usize)..]),
             None => ParseResult::new_bad("Byte not found"),
         }
@@ -76,7 +77,7 @@ struct BytesUntilEither<const N: usize>([u8; N], bool);
 impl<'i, const N: usize> Parser<'i, &'i [u8]> for BytesUntilEither<N> {
     #[inline]
     fn parse(&self, input: &'i [u8]) -> ParseResult<'i, &'i [u8]> {
-        match input.iter().position(|v| self.0.contains(v)) {
+        match find_either_byte(input, &self.0) {
             Some(pos) => ParseResult::Good(&input[..pos], &input[pos + (self.1 as usize)..]),
             None => ParseResult::new_bad("Either byte not found"),
         }
@@ -160,6 +161,11 @@ pub fn bytes_until<'i>(b: u8, eat: bool) -> impl Parser<'i, &'i [u8]> {
     BytesUntil(b, eat)
 }
 
+#[inline]
+pub fn bytes_until_either<'i, const N: usize>(b: [u8; N], eat: bool) -> impl Parser<'i, &'i [u8]> {
+    BytesUntilEither(b, eat)
+}
+
 #[inline]
 pub fn word<'i>() -> impl Parser<'i, &'i [u8]> {
     Word
@@ -0,0 +1,182 @@
+use crate::grid::Grid;
+use crate::parse::{ParseResult, Parser};
+
+/// A rectangular text block turned on its side, so each column of the text can be read and
+/// parsed top-to-bottom as a contiguous slice. Short lines are padded to the widest one.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Columns {
+    grid: Grid<u8>,
+}
+
+impl Columns {
+    pub fn new(input: &[u8], padding: u8) -> Self {
+        Self {
+            grid: transpose_block(input, padding),
+        }
+    }
+
+    /// The transposed block, where `x` is the line and `y` is the column in the text.
+    #[inline]
+    pub fn grid(&self) -> &Grid<u8> {
+        &self.grid
+    }
+
+    /// The text column at `x`, from top to bottom.
+    #[inline]
+    pub fn column(&self, x: usize) -> &[u8] {
+        self.grid.row(x)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.grid.height()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.grid.height() == 0
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.grid.rows().map(|(_, col)| col)
+    }
+
+    /// Split the block at the column offsets, e.g. `[4, 8]` splits it into the columns `0..4`,
+    /// `4..8` and `8..`.
+    pub fn split_at(&self, offsets: &[usize]) -> Vec<Columns> {
+        let lines = self.grid.width();
+        let mut res = Vec::with_capacity(offsets.len() + 1);
+        let mut start = 0;
+
+        for end in offsets
+            .iter()
+            .copied()
+            .chain(std::iter::once(self.len()))
+            .map(|end| end.min(self.len()))
+        {
+            let mut grid = Grid::new_with_value(lines, end.saturating_sub(start), b' ');
+            for x in start..end {
+                for (y, v) in self.column(x).iter().enumerate() {
+                    grid[(y, x - start)] = *v;
+                }
+            }
+
+            res.push(Columns { grid });
+            start = end.max(start);
+        }
+
+        res
+    }
+
+    /// Parse every column top-to-bottom.
+    #[inline]
+    pub fn parse_each<'a, P, T>(&'a self, parser: P) -> impl Iterator<Item = ParseResult<'a, T>>
+    where
+        P: Parser<'a, T>,
+    {
+        self.iter().map(move |col| parser.parse(col))
+    }
+
+    /// Parse the columns at the offsets top-to-bottom. Offsets past the widest line are skipped.
+    #[inline]
+    pub fn parse_at<'a, P, T>(
+        &'a self,
+        offsets: impl IntoIterator<Item = usize> + 'a,
+        parser: P,
+    ) -> impl Iterator<Item = ParseResult<'a, T>>
+    where
+        P: Parser<'a, T>,
+    {
+        offsets
+            .into_iter()
+            .filter(|x| *x < self.len())
+            .map(move |x| parser.parse(self.column(x)))
+    }
+}
+
+/// Transpose a block of text lines into a grid where each row is a column of the text. Lines
+/// shorter than the widest one are padded, and the block ends at an empty line or the end of
+/// the input.
+pub fn transpose_block(input: &[u8], padding: u8) -> Grid<u8> {
+    let lines: Vec<&[u8]> = input
+        .split(|v| *v == b'\n')
+        .take_while(|line| !line.is_empty())
+        .collect();
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+
+    let mut grid = Grid::new_with_value(lines.len(), width, padding);
+    for (y, line) in lines.iter().enumerate() {
+        for (x, v) in line.iter().enumerate() {
+            grid[(y, x)] = *v;
+        }
+    }
+
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{any_byte, unsigned_int, ws};
+
+    const CRATES: &[u8] = b"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1";
+
+    #[test]
+    fn columns_are_transposed() {
+        let columns = Columns::new(CRATES, b' ');
+
+        assert_eq!(columns.len(), 11);
+        assert_eq!(columns.column(1), b" NZ1");
+        assert_eq!(columns.column(9), b"  P3");
+        assert_eq!(columns.grid()[(1, 5)], b'C');
+    }
+
+    #[test]
+    fn columns_can_be_parsed() {
+        let columns = Columns::new(CRATES, b' ');
+        let stack_parser = ws()
+            .and_instead(any_byte().in_range(b'A'..=b'Z').repeat::<Vec<_>>())
+            .and(unsigned_int::<usize>());
+
+        let stacks: Vec<(Vec<u8>, usize)> = columns
+            .parse_at((1..columns.len()).step_by(4), stack_parser)
+            .map(|res| res.unwrap())
+            .collect();
+        assert_eq!(
+            stacks,
+            vec![
+                (b"NZ".to_vec(), 1),
+                (b"DCM".to_vec(), 2),
+                (b"P".to_vec(), 3)
+            ]
+        );
+
+        let bracket_columns = columns
+            .parse_each(ws().and_instead(b'['))
+            .filter(|res| matches!(res, ParseResult::Good(..)))
+            .count();
+        assert_eq!(bracket_columns, 3);
+    }
+
+    #[test]
+    fn columns_can_be_split() {
+        let parts = Columns::new(b"ab  12\ncd  34", b' ').split_at(&[2, 4]);
+
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].iter().collect::<Vec<_>>(), vec![b"ac", b"bd"]);
+        assert_eq!(parts[1].iter().collect::<Vec<_>>(), vec![b"  ", b"  "]);
+        assert_eq!(
+            parts[2]
+                .parse_each(unsigned_int::<u8>())
+                .map(|res| res.unwrap())
+                .collect::<Vec<_>>(),
+            vec![13, 24]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::geo::Point;
+use crate::parse::{ParseResult, Parser};
+use std::marker::PhantomData;
+use std::simd::SimdElement;
+
+pub struct Coords<P, T, PD, TD, const N: usize> {
+    value: P,
+    delimiter: PD,
+    spooky_ghost: PhantomData<(T, TD)>,
+}
+
+impl<P, T, PD, TD, const N: usize> Copy for Coords<P, T, PD, TD, N>
+where
+    P: Copy,
+    PD: Copy,
+{
+}
+
+impl<P, T, PD, TD, const N: usize> Clone for Coords<P, T, PD, TD, N>
+where
+    P: Clone,
+    PD: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            delimiter: self.delimiter.clone(),
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<'i, P, T, PD, TD, const N: usize> Parser<'i, [T; N]> for Coords<P, T, PD, TD, N>
+where
+    P: Parser<'i, T>,
+    PD: Parser<'i, TD>,
+    T: Copy + Default,
+{
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, [T; N]> {
+        let mut coords = [T::default(); N];
+        let mut current_input = input;
+
+        for (i, coord) in coords.iter_mut().enumerate() {
+            if i > 0 {
+                current_input = match self.delimiter.parse(current_input) {
+                    ParseResult::Good(_, new_input) => new_input,
+                    ParseResult::Bad(err) => {
+                        return ParseResult::wrap_bad(err, "Coords is missing a delimiter")
+                    }
+                };
+            }
+
+            match self.value.parse(current_input) {
+                ParseResult::Good(v, new_input) => {
+                    *coord = v;
+                    current_input = new_input;
+                }
+                ParseResult::Bad(err) => {
+                    return ParseResult::wrap_bad(err, "Coords is missing a value")
+                }
+            }
+        }
+
+        ParseResult::Good(coords, current_input)
+    }
+}
+
+/// Parse exactly `N` values with a delimiter between them, like the `1,0,3` of a 3D coordinate.
+#[inline]
+pub fn coords<'i, P, T, PD, TD, const N: usize>(value: P, delimiter: PD) -> Coords<P, T, PD, TD, N>
+where
+    P: Parser<'i, T>,
+    PD: Parser<'i, TD>,
+{
+    Coords {
+        value,
+        delimiter,
+        spooky_ghost: Default::default(),
+    }
+}
+
+/// Parse a 2D point like `3,4` or `3, 4`.
+#[inline]
+pub fn point<'i, P, T, PD, TD>(value: P, delimiter: PD) -> impl Parser<'i, Point<T>>
+where
+    P: Parser<'i, T>,
+    PD: Parser<'i, TD>,
+    T: SimdElement + Default,
+{
+    coords::<P, T, PD, TD, 2>(value, delimiter).map(|[x, y]| Point::new(x, y))
+}
+
+/// Parse a 3D point like `1,0,3` into a tuple.
+#[inline]
+pub fn point3<'i, P, T, PD, TD>(value: P, delimiter: PD) -> impl Parser<'i, (T, T, T)>
+where
+    P: Parser<'i, T>,
+    PD: Parser<'i, TD>,
+    T: Copy + Default,
+{
+    coords::<P, T, PD, TD, 3>(value, delimiter).map(|[x, y, z]| (x, y, z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{signed_int, token, unsigned_int};
+
+    #[test]
+    fn coords_parse_exactly() {
+        let parser = coords::<_, u16, _, _, 3>(unsigned_int(), b',');
+
+        assert_eq!(
+            parser.parse(b"1,0,3~1,2,3"),
+            ParseResult::Good([1, 0, 3], b"~1,2,3")
+        );
+        assert_eq!(
+            parser.parse(b"1,0~1"),
+            ParseResult::new_bad_slice(&["u8 not matched", "Coords is missing a delimiter"])
+        );
+        assert_eq!(
+            parser
+                .delimited_by(b'~')
+                .repeat_n::<[[u16; 3]; 2]>(2)
+                .parse(b"1,0,3~1,2,3"),
+            ParseResult::Good([[1, 0, 3], [1, 2, 3]], b"")
+        );
+    }
+
+    #[test]
+    fn points_parse() {
+        assert_eq!(
+            point(signed_int::<i32>(), token(b',')).parse(b"-3, 4 -> 5,6"),
+            ParseResult::Good(Point::new(-3, 4), b" -> 5,6")
+        );
+        assert_eq!(
+            point3(unsigned_int::<u32>(), b' ').parse(b"5 6 7"),
+            ParseResult::Good((5, 6, 7), b"")
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::parse::{everything, unsigned_int, ParseResult, Parser};
+use crate::utils::gather_target::GatherTarget;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GrammarRule {
+    Literal(Vec<u8>),
+    /// Each alternative is a sequence of rule ids.
+    Alternatives(Vec<Vec<usize>>),
+}
+
+/// A grammar built at runtime, e.g. from rules given in the puzzle input. Matching finds every
+/// way a rule can match and memoises it per rule and position, so ambiguous and looping rules
+/// like `8: 42 | 42 8` work without blowing up. Left recursion is not supported.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Grammar {
+    rules: Vec<Option<GrammarRule>>,
+}
+
+impl Grammar {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_rule(&mut self, id: usize, rule: GrammarRule) {
+        if id >= self.rules.len() {
+            self.rules.resize(id + 1, None);
+        }
+
+        self.rules[id] = Some(rule);
+    }
+
+    #[inline]
+    pub fn rule(&self, id: usize) -> Option<&GrammarRule> {
+        self.rules.get(id).and_then(|r| r.as_ref())
+    }
+
+    /// All the lengths of the input's prefixes that the rule matches, in ascending order.
+    pub fn match_lengths(&self, id: usize, input: &[u8]) -> Vec<usize> {
+        Matcher::new(self, input).ends(id, 0).to_vec()
+    }
+
+    /// Check if the rule matches the entire input.
+    pub fn matches(&self, id: usize, input: &[u8]) -> bool {
+        Matcher::new(self, input).ends(id, 0).last().copied() == Some(input.len())
+    }
+
+    /// Get a parser that parses the longest match of the rule.
+    #[inline]
+    pub fn rule_parser(&self, id: usize) -> RuleParser<'_> {
+        RuleParser { grammar: self, id }
+    }
+
+    /// Parse rules in the form of `0: 4 1 5`, `1: 2 3 | 3 2` and `4: "a"`, one per line.
+    pub fn parser<'i>() -> impl Parser<'i, Grammar> {
+        unsigned_int::<usize>()
+            .and_discard(b": ")
+            .and(
+                everything()
+                    .quoted_by(b'"', b'"')
+                    .map(|lit| GrammarRule::Literal(lit.to_vec()))
+                    .or(unsigned_int::<usize>()
+                        .delimited_by(b' ')
+                        .repeat::<Vec<_>>()
+                        .delimited_by(b" | ")
+                        .repeat::<Vec<_>>()
+                        .map(GrammarRule::Alternatives)),
+            )
+            .delimited_by(b'\n')
+            .repeat()
+    }
+}
+
+impl GatherTarget<(usize, GrammarRule)> for Grammar {
+    fn start_gathering(size_hint: usize) -> Self {
+        Self {
+            rules: Vec::with_capacity(size_hint),
+        }
+    }
+
+    fn gather_into(&mut self, _index: usize, (id, rule): (usize, GrammarRule)) -> bool {
+        self.set_rule(id, rule);
+        false
+    }
+}
+
+struct Matcher<'g, 'i> {
+    grammar: &'g Grammar,
+    input: &'i [u8],
+    memo: FxHashMap<(usize, usize), Rc<[usize]>>,
+    in_progress: FxHashSet<(usize, usize)>,
+}
+
+impl<'g, 'i> Matcher<'g, 'i> {
+    fn new(grammar: &'g Grammar, input: &'i [u8]) -> Self {
+        Self {
+            grammar,
+            input,
+            memo: FxHashMap::default(),
+            in_progress: FxHashSet::default(),
+        }
+    }
+
+    fn ends(&mut self, id: usize, pos: usize) -> Rc<[usize]> {
+        if let Some(ends) = self.memo.get(&(id, pos)) {
+            return ends.clone();
+        }
+        if !self.in_progress.insert((id, pos)) {
+            return Rc::from([]);
+        }
+
+        let mut ends = Vec::new();
+        match self.grammar.rule(id) {
+            Some(GrammarRule::Literal(lit)) => {
+                if self.input[pos..].starts_with(lit) {
+                    ends.push(pos + lit.len());
+                }
+            }
+            Some(GrammarRule::Alternatives(alts)) => {
+                for seq in alts.iter() {
+                    let mut current = vec![pos];
+                    for sub_id in seq.iter() {
+                        let mut next = Vec::with_capacity(current.len());
+                        for p in current {
+                            next.extend(self.ends(*sub_id, p).iter());
+                        }
+                        next.sort_unstable();
+                        next.dedup();
+
+                        current = next;
+                        if current.is_empty() {
+                            break;
+                        }
+                    }
+
+                    ends.extend(current);
+                }
+
+                ends.sort_unstable();
+                ends.dedup();
+            }
+            None => {}
+        }
+
+        let ends: Rc<[usize]> = Rc::from(ends);
+        self.in_progress.remove(&(id, pos));
+        self.memo.insert((id, pos), ends.clone());
+
+        ends
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct RuleParser<'g> {
+    grammar: &'g Grammar,
+    id: usize,
+}
+
+impl<'i, 'g> Parser<'i, &'i [u8]> for RuleParser<'g> {
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, &'i [u8]> {
+        match Matcher::new(self.grammar, input).ends(self.id, 0).last() {
+            Some(end) => ParseResult::Good(&input[..*end], &input[*end..]),
+            None => ParseResult::new_bad("Grammar rule did not match"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &[u8] = b"0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: \"a\"
+5: \"b\"";
+
+    #[test]
+    fn grammar_parses_and_matches() {
+        let grammar = Grammar::parser().parse(EXAMPLE).unwrap();
+
+        assert_eq!(grammar.rule(4), Some(&GrammarRule::Literal(b"a".to_vec())));
+        assert_eq!(
+            grammar.rule(1),
+            Some(&GrammarRule::Alternatives(vec![vec![2, 3], vec![3, 2]]))
+        );
+
+        assert!(grammar.matches(0, b"ababbb"));
+        assert!(grammar.matches(0, b"abbbab"));
+        assert!(!grammar.matches(0, b"bababa"));
+        assert!(!grammar.matches(0, b"aaabbb"));
+        assert!(!grammar.matches(0, b"aaaabbb"));
+
+        assert_eq!(
+            grammar.rule_parser(0).parse(b"aaaabbb"),
+            ParseResult::Good(b"aaaabb".as_slice(), b"b")
+        );
+        assert_eq!(
+            grammar.rule_parser(0).parse(b"bababa"),
+            ParseResult::new_bad("Grammar rule did not match")
+        );
+    }
+
+    #[test]
+    fn grammar_handles_loops() {
+        let grammar = Grammar::parser()
+            .parse(b"0: 8 11\n8: 42 | 42 8\n11: 42 31 | 42 11 31\n42: \"a\"\n31: \"b\"")
+            .unwrap();
+
+        assert!(grammar.matches(0, b"aab"));
+        assert!(grammar.matches(0, b"aaaab"));
+        assert!(grammar.matches(0, b"aaaabb"));
+        assert!(!grammar.matches(0, b"ab"));
+        assert!(!grammar.matches(0, b"aabbb"));
+        assert_eq!(grammar.match_lengths(11, b"aabbb"), vec![4]);
+        assert_eq!(grammar.match_lengths(8, b"aaab"), vec![1, 2, 3]);
+    }
+}
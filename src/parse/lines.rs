@@ -0,0 +1,167 @@
+use crate::parse::{ParseResult, Parser};
+use arrayvec::ArrayVec;
+use rayon::prelude::*;
+
+const MIN_CHUNK_SIZE: usize = 4096;
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct LineError {
+    pub line: usize,
+    pub errors: ArrayVec<&'static str, 4>,
+}
+
+/// Parse each line of the input with the parser, spreading newline-aligned chunks of the input
+/// across rayon's thread pool. The results are in the same order as the lines, and a trailing
+/// newline does not produce an extra line, and a `\r` before a newline is not part of the line.
+/// If any line fails or is not parsed to its end, the first failure is returned with its line
+/// number (starting at 1).
+pub fn parse_lines_par<'i, P, T>(input: &'i [u8], parser: P) -> Result<Vec<T>, LineError>
+where
+    P: Parser<'i, T> + Sync,
+    T: Send,
+{
+    let chunk_size = (input.len() / (rayon::current_num_threads() * 4)).max(MIN_CHUNK_SIZE);
+    let chunks = split_chunks(input, chunk_size);
+
+    let results: Vec<(usize, Result<Vec<T>, LineError>)> = chunks
+        .par_iter()
+        .map(|chunk| parse_chunk(chunk, parser))
+        .collect();
+
+    let mut res = Vec::with_capacity(results.iter().map(|(n, _)| *n).sum());
+    let mut line_offset = 0;
+    for (line_count, chunk_res) in results {
+        match chunk_res {
+            Ok(values) => res.extend(values),
+            Err(err) => {
+                return Err(LineError {
+                    line: err.line + line_offset,
+                    errors: err.errors,
+                })
+            }
+        }
+
+        line_offset += line_count;
+    }
+
+    Ok(res)
+}
+
+fn split_chunks(input: &[u8], chunk_size: usize) -> Vec<&[u8]> {
+    let mut chunks = Vec::with_capacity((input.len() / chunk_size) + 1);
+    let mut rest = input;
+
+    while rest.len() > chunk_size {
+        match rest[chunk_size..].iter().position(|v| *v == b'\n') {
+            Some(pos) => {
+                let (chunk, next) = rest.split_at(chunk_size + pos + 1);
+                chunks.push(chunk);
+                rest = next;
+            }
+            None => break,
+        }
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+
+    chunks
+}
+
+fn parse_chunk<'i, P, T>(chunk: &'i [u8], parser: P) -> (usize, Result<Vec<T>, LineError>)
+where
+    P: Parser<'i, T>,
+{
+    let mut values = Vec::new();
+    let mut line_count = 0;
+
+    for line in chunk
+        .strip_suffix(b"\n")
+        .unwrap_or(chunk)
+        .split(|v| *v == b'\n')
+    {
+        line_count += 1;
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        let errors = match parser.parse(line) {
+            ParseResult::Good(v, []) => {
+                values.push(v);
+                continue;
+            }
+            ParseResult::Good(..) => {
+                let mut errors = ArrayVec::new();
+                errors.push("Line was not fully parsed");
+                errors
+            }
+            ParseResult::Bad(errors) => errors,
+        };
+
+        return (
+            line_count,
+            Err(LineError {
+                line: line_count,
+                errors,
+            }),
+        );
+    }
+
+    (line_count, Ok(values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{unsigned_int, word};
+
+    #[test]
+    fn parse_lines_par_keeps_order() {
+        let input: Vec<u8> = (0..20000u32)
+            .flat_map(|i| format!("{},{}\n", i, i * 2).into_bytes())
+            .collect();
+        let parser = unsigned_int::<u32>()
+            .and_discard(b',')
+            .and(unsigned_int::<u32>());
+
+        let res = parse_lines_par(&input, parser).unwrap();
+        assert_eq!(res.len(), 20000);
+        assert!(res
+            .iter()
+            .enumerate()
+            .all(|(i, v)| *v == (i as u32, i as u32 * 2)));
+
+        assert_eq!(
+            parse_lines_par(b"12\n34", unsigned_int::<u32>()),
+            Ok(vec![12, 34])
+        );
+        assert_eq!(parse_lines_par(b"", unsigned_int::<u32>()), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_lines_par_reports_line_number() {
+        let mut input: Vec<u8> = b"word\n".as_slice().repeat(20000);
+        input.extend_from_slice(b"1337\nword\n42\n");
+
+        assert_eq!(
+            parse_lines_par(&input, word()),
+            Err(LineError {
+                line: 20001,
+                errors: ArrayVec::try_from(["not a word"].as_slice()).unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_lines_par_needs_whole_lines() {
+        assert_eq!(
+            parse_lines_par(b"12\r\n34\r\n", unsigned_int::<u32>()),
+            Ok(vec![12, 34])
+        );
+        assert_eq!(
+            parse_lines_par(b"12\n34abc\n56\n", unsigned_int::<u32>()),
+            Err(LineError {
+                line: 2,
+                errors: ArrayVec::try_from(["Line was not fully parsed"].as_slice()).unwrap(),
+            })
+        );
+    }
+}
@@ -3,11 +3,28 @@ use std::marker::PhantomData;
 use std::ops::RangeBounds;
 
 use crate::utils::gather_target::GatherTarget;
+use crate::utils::scan::{find_byte, find_subslice};
 
 pub use bytes::*;
 pub use choice::choice;
+pub use columns::{transpose_block, Columns};
+pub use coords::{coords, point, point3};
+pub use grammar::{Grammar, GrammarRule};
 pub use int::{digit, hex_byte, hex_digit, signed_int, unsigned_int};
+pub use lines::{parse_lines_par, LineError};
+pub use permutation::{optional, permutation, record, Record};
+pub use range::{range, range_list};
+pub use seq::from_fn;
 pub use skip::skip;
+pub use stream::{parse_stream, RecordReader, StreamError, StreamIterator};
+pub use token::{keyword, token, tokens_mode, ws};
+pub use trace::{trace_parse, Trace, TraceEvent, TraceEventKind};
+pub use utf8::{any_char, as_str, take_while_char, word_str};
+
+#[cfg(all(unix, feature = "mmap"))]
+pub use stream::MappedFile;
+
+pub use crate::seq;
 
 use crate::parse::cap::{CappedBy, QuotedBy};
 use crate::parse::repeat::RepeatFold;
@@ -18,19 +35,31 @@ use or::Or;
 use repeat::{Count, DelimitedBy, Repeat};
 use rewind::Rewind;
 use skip::{SkipAll, ThenSkip};
+use trace::Traced;
 use vanguard::Vanguard;
 
 mod and;
 mod bytes;
 mod cap;
 mod choice;
+mod columns;
+mod coords;
 mod filter;
+mod grammar;
 mod int;
+mod lines;
 mod map;
 mod or;
+mod permutation;
+mod range;
 mod repeat;
 mod rewind;
+mod seq;
 mod skip;
+mod stream;
+mod token;
+mod trace;
+mod utf8;
 mod vanguard;
 
 pub trait Parser<'i, T>: Sized + Copy {
@@ -80,6 +109,12 @@ pub trait Parser<'i, T>: Sized + Copy {
         ParseResult::new_bad("No parsable last input found")
     }
 
+    /// Parse a `&str` input. Any `&str` output from the UTF-8 aware parsers will point into it.
+    #[inline]
+    fn parse_str(&self, input: &'i str) -> ParseResult<'i, T> {
+        self.parse(input.as_bytes())
+    }
+
     #[inline]
     #[allow(unused_variables)]
     fn parse_at_index(&self, input: &'i [u8], index: usize) -> ParseResult<'i, T> {
@@ -306,6 +341,13 @@ pub trait Parser<'i, T>: Sized + Copy {
     {
         Vanguard::new(self, vanguard)
     }
+
+    /// Record enter and exit events for this parser when run through `trace_parse`. This
+    /// compiles down to the wrapped parser unless the `parse-trace` feature is enabled.
+    #[inline]
+    fn traced(self, name: &'static str) -> Traced<Self, T> {
+        Traced::new(self, name)
+    }
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -355,7 +397,7 @@ impl<'i> Parser<'i, u8> for u8 {
 
     #[inline]
     fn first_parsable_in(&self, input: &'i [u8]) -> ParseResult<'i, (u8, usize)> {
-        match input.iter().position(|v| *v == *self) {
+        match find_byte(input, *self) {
             Some(index) => ParseResult::Good((*self, index), &input[index + 1..]),
             None => ParseResult::new_bad("Byte not found in input"),
         }
@@ -379,12 +421,11 @@ impl<'i, 's> Parser<'i, &'i [u8]> for &'s [u8] {
 
     #[inline]
     fn first_parsable_in(&self, input: &'i [u8]) -> ParseResult<'i, (&'i [u8], usize)> {
-        match input
-            .windows(self.len())
-            .enumerate()
-            .find(|(_, w)| w == self)
-        {
-            Some((index, data)) => ParseResult::Good((data, index), &input[index + self.len()..]),
+        match find_subslice(input, self) {
+            Some(index) => ParseResult::Good(
+                (&input[index..index + self.len()], index),
+                &input[index + self.len()..],
+            ),
             None => ParseResult::new_bad("Byte slice not found in input"),
         }
     }
@@ -407,12 +448,10 @@ impl<'i, 's, const N: usize> Parser<'i, &'i [u8]> for &'s [u8; N] {
 
     #[inline]
     fn first_parsable_in(&self, input: &'i [u8]) -> ParseResult<'i, (&'i [u8], usize)> {
-        match input
-            .array_windows::<N>()
-            .enumerate()
-            .find(|(_, w)| w == self)
-        {
-            Some((index, data)) => ParseResult::Good((data, index), &input[index + N..]),
+        match find_subslice(input, self.as_slice()) {
+            Some(index) => {
+                ParseResult::Good((&input[index..index + N], index), &input[index + N..])
+            }
             None => ParseResult::new_bad("Byte slice not found in input"),
         }
     }
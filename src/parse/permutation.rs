@@ -0,0 +1,409 @@
+use crate::parse::{ParseResult, Parser};
+use std::marker::PhantomData;
+
+/// A member of a permutation. Any parser is a required member, while `optional(p)` makes a
+/// member that can be left out.
+pub trait PermutationMember<'i, T, O>: Copy {
+    fn parse_member(&self, input: &'i [u8]) -> ParseResult<'i, T>;
+    fn finish(value: Option<T>) -> Option<O>;
+}
+
+impl<'i, T, P> PermutationMember<'i, T, T> for P
+where
+    P: Parser<'i, T>,
+{
+    #[inline]
+    fn parse_member(&self, input: &'i [u8]) -> ParseResult<'i, T> {
+        self.parse(input)
+    }
+
+    #[inline]
+    fn finish(value: Option<T>) -> Option<T> {
+        value
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Optional<P>(P);
+
+impl<'i, T, P> PermutationMember<'i, T, Option<T>> for Optional<P>
+where
+    P: Parser<'i, T>,
+{
+    #[inline]
+    fn parse_member(&self, input: &'i [u8]) -> ParseResult<'i, T> {
+        self.0.parse(input)
+    }
+
+    #[inline]
+    fn finish(value: Option<T>) -> Option<Option<T>> {
+        Some(value)
+    }
+}
+
+pub trait Permutations<'i, T, O>: Copy {
+    fn parse_permutation(&self, input: &'i [u8]) -> ParseResult<'i, O>;
+}
+
+macro_rules! impl_permutations {
+    ($(($m:ident, $t:ident, $o:ident, $idx:tt)),+) => {
+        impl<'i, $($t, $o, $m: PermutationMember<'i, $t, $o>),+>
+            Permutations<'i, ($($t,)+), ($($o,)+)> for ($($m,)+)
+        {
+            fn parse_permutation(&self, input: &'i [u8]) -> ParseResult<'i, ($($o,)+)> {
+                let mut values: ($(Option<$t>,)+) = ($(None::<$t>,)+);
+                let mut input = input;
+
+                'outer: loop {
+                    $(
+                        if values.$idx.is_none() {
+                            if let ParseResult::Good(v, new_input) = self.$idx.parse_member(input) {
+                                values.$idx = Some(v);
+                                input = new_input;
+                                continue 'outer;
+                            }
+                        }
+                    )+
+
+                    break;
+                }
+
+                ParseResult::Good(
+                    ($(
+                        match <$m as PermutationMember<'i, $t, $o>>::finish(values.$idx) {
+                            Some(v) => v,
+                            None => return ParseResult::new_bad("Permutation is missing a member"),
+                        },
+                    )+),
+                    input,
+                )
+            }
+        }
+    };
+}
+
+impl_permutations!((M1, T1, O1, 0), (M2, T2, O2, 1));
+impl_permutations!((M1, T1, O1, 0), (M2, T2, O2, 1), (M3, T3, O3, 2));
+impl_permutations!(
+    (M1, T1, O1, 0),
+    (M2, T2, O2, 1),
+    (M3, T3, O3, 2),
+    (M4, T4, O4, 3)
+);
+impl_permutations!(
+    (M1, T1, O1, 0),
+    (M2, T2, O2, 1),
+    (M3, T3, O3, 2),
+    (M4, T4, O4, 3),
+    (M5, T5, O5, 4)
+);
+impl_permutations!(
+    (M1, T1, O1, 0),
+    (M2, T2, O2, 1),
+    (M3, T3, O3, 2),
+    (M4, T4, O4, 3),
+    (M5, T5, O5, 4),
+    (M6, T6, O6, 5)
+);
+impl_permutations!(
+    (M1, T1, O1, 0),
+    (M2, T2, O2, 1),
+    (M3, T3, O3, 2),
+    (M4, T4, O4, 3),
+    (M5, T5, O5, 4),
+    (M6, T6, O6, 5),
+    (M7, T7, O7, 6)
+);
+impl_permutations!(
+    (M1, T1, O1, 0),
+    (M2, T2, O2, 1),
+    (M3, T3, O3, 2),
+    (M4, T4, O4, 3),
+    (M5, T5, O5, 4),
+    (M6, T6, O6, 5),
+    (M7, T7, O7, 6),
+    (M8, T8, O8, 7)
+);
+
+pub struct Permutation<C, T, O> {
+    members: C,
+    spooky_ghost: PhantomData<(T, O)>,
+}
+
+impl<C, T, O> Copy for Permutation<C, T, O> where C: Copy {}
+
+impl<C, T, O> Clone for Permutation<C, T, O>
+where
+    C: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            members: self.members.clone(),
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<'i, C, T, O> Parser<'i, O> for Permutation<C, T, O>
+where
+    C: Permutations<'i, T, O>,
+{
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, O> {
+        self.members.parse_permutation(input)
+    }
+}
+
+/// A record is a struct that is filled in from fields in any order, like a passport with
+/// `key:value` pairs. The fields are identified by their index, which must be below 64.
+pub trait Record<F>: Default {
+    /// The bit mask of the field indices that must be present.
+    const REQUIRED: u64;
+
+    fn field_index(field: &F) -> usize;
+    fn set_field(&mut self, field: F);
+}
+
+pub struct RecordParser<R, PF, F, PD, TD> {
+    field_parser: PF,
+    delim_parser: PD,
+    spooky_ghost: PhantomData<(R, F, TD)>,
+}
+
+impl<R, PF, F, PD, TD> Copy for RecordParser<R, PF, F, PD, TD>
+where
+    PF: Copy,
+    PD: Copy,
+{
+}
+
+impl<R, PF, F, PD, TD> Clone for RecordParser<R, PF, F, PD, TD>
+where
+    PF: Clone,
+    PD: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            field_parser: self.field_parser.clone(),
+            delim_parser: self.delim_parser.clone(),
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<'i, R, PF, F, PD, TD> Parser<'i, R> for RecordParser<R, PF, F, PD, TD>
+where
+    R: Record<F>,
+    PF: Parser<'i, F>,
+    PD: Parser<'i, TD>,
+{
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, R> {
+        let mut record = R::default();
+        let mut seen = 0u64;
+        let mut current_input = input;
+
+        loop {
+            let field_input = if seen == 0 {
+                current_input
+            } else {
+                match self.delim_parser.parse(current_input) {
+                    ParseResult::Good(_, new_input) => new_input,
+                    ParseResult::Bad(_) => break,
+                }
+            };
+
+            match self.field_parser.parse(field_input) {
+                ParseResult::Good(field, new_input) => {
+                    let bit = 1u64 << R::field_index(&field);
+                    if seen & bit != 0 {
+                        return ParseResult::new_bad("Record has a duplicated field");
+                    }
+
+                    seen |= bit;
+                    record.set_field(field);
+                    current_input = new_input;
+                }
+                ParseResult::Bad(err) => {
+                    if seen == 0 {
+                        return ParseResult::wrap_bad(err, "Record has no fields");
+                    }
+
+                    break;
+                }
+            }
+        }
+
+        if seen & R::REQUIRED != R::REQUIRED {
+            return ParseResult::new_bad("Record is missing a required field");
+        }
+
+        ParseResult::Good(record, current_input)
+    }
+}
+
+/// Parse each of the members exactly once, in any order. The result is in the order of the
+/// members, and members wrapped in `optional` are given as `Option`.
+#[inline]
+pub fn permutation<'i, C, T, O>(members: C) -> Permutation<C, T, O>
+where
+    C: Permutations<'i, T, O>,
+{
+    Permutation {
+        members,
+        spooky_ghost: Default::default(),
+    }
+}
+
+/// Allow this member of a permutation to be left out.
+#[inline]
+pub fn optional<'i, P, T>(parser: P) -> Optional<P>
+where
+    P: Parser<'i, T>,
+{
+    Optional(parser)
+}
+
+/// Parse the fields of a record separated by the delimiter, failing on duplicated fields or when
+/// any of the record's required fields are missing.
+#[inline]
+pub fn record<'i, R, PF, F, PD, TD>(
+    field_parser: PF,
+    delim_parser: PD,
+) -> RecordParser<R, PF, F, PD, TD>
+where
+    R: Record<F>,
+    PF: Parser<'i, F>,
+    PD: Parser<'i, TD>,
+{
+    RecordParser {
+        field_parser,
+        delim_parser,
+        spooky_ghost: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{choice, take_while, unsigned_int, word};
+
+    #[test]
+    fn permutation_permutes() {
+        let parser = permutation((
+            b"w=".and_instead(unsigned_int::<u32>()).then_skip(b' '),
+            b"h=".and_instead(unsigned_int::<u32>()).then_skip(b' '),
+            optional(b"name=".and_instead(word()).then_skip(b' ')),
+        ));
+
+        assert_eq!(
+            parser.parse(b"w=3 h=4 name=box rest"),
+            ParseResult::Good((3, 4, Some(b"box".as_slice())), b"rest")
+        );
+        assert_eq!(
+            parser.parse(b"name=box h=4 w=3 rest"),
+            ParseResult::Good((3, 4, Some(b"box".as_slice())), b"rest")
+        );
+        assert_eq!(
+            parser.parse(b"h=4 w=3 w=5 "),
+            ParseResult::Good((3, 4, None), b"w=5 ")
+        );
+        assert_eq!(
+            parser.parse(b"h=4 name=box "),
+            ParseResult::new_bad("Permutation is missing a member")
+        );
+    }
+
+    #[derive(Default, Debug, Eq, PartialEq)]
+    struct Passport<'i> {
+        birth_year: u32,
+        eye_color: &'i [u8],
+        passport_id: &'i [u8],
+        country_id: Option<u32>,
+    }
+
+    enum PassportField<'i> {
+        BirthYear(u32),
+        EyeColor(&'i [u8]),
+        PassportId(&'i [u8]),
+        CountryId(u32),
+    }
+
+    impl<'i> Record<PassportField<'i>> for Passport<'i> {
+        const REQUIRED: u64 = 0b0111;
+
+        fn field_index(field: &PassportField<'i>) -> usize {
+            match field {
+                PassportField::BirthYear(_) => 0,
+                PassportField::EyeColor(_) => 1,
+                PassportField::PassportId(_) => 2,
+                PassportField::CountryId(_) => 3,
+            }
+        }
+
+        fn set_field(&mut self, field: PassportField<'i>) {
+            match field {
+                PassportField::BirthYear(v) => self.birth_year = v,
+                PassportField::EyeColor(v) => self.eye_color = v,
+                PassportField::PassportId(v) => self.passport_id = v,
+                PassportField::CountryId(v) => self.country_id = Some(v),
+            }
+        }
+    }
+
+    fn passport_parser<'i>() -> impl Parser<'i, Passport<'i>> {
+        let value = || take_while(|c| c != b' ' && c != b'\n');
+
+        record(
+            choice((
+                b"byr:".and_instead(unsigned_int().map(PassportField::BirthYear)),
+                b"ecl:".and_instead(value().map(PassportField::EyeColor)),
+                b"pid:".and_instead(value().map(PassportField::PassportId)),
+                b"cid:".and_instead(unsigned_int().map(PassportField::CountryId)),
+            )),
+            b' '.or(b'\n'),
+        )
+    }
+
+    #[test]
+    fn record_fills_in_fields() {
+        let passports: Vec<Passport> = passport_parser()
+            .delimited_by(b"\n\n")
+            .repeat()
+            .parse(b"ecl:gry pid:860033327\nbyr:1937 cid:147\n\npid:028048884 byr:1929\necl:amb")
+            .unwrap();
+
+        assert_eq!(
+            passports,
+            vec![
+                Passport {
+                    birth_year: 1937,
+                    eye_color: b"gry",
+                    passport_id: b"860033327",
+                    country_id: Some(147),
+                },
+                Passport {
+                    birth_year: 1929,
+                    eye_color: b"amb",
+                    passport_id: b"028048884",
+                    country_id: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn record_validates_fields() {
+        assert_eq!(
+            passport_parser().parse(b"ecl:gry pid:860033327"),
+            ParseResult::new_bad("Record is missing a required field")
+        );
+        assert_eq!(
+            passport_parser().parse(b"ecl:gry pid:860033327 byr:1937 ecl:amb"),
+            ParseResult::new_bad("Record has a duplicated field")
+        );
+        assert_eq!(
+            passport_parser().parse(b"hgt:183cm"),
+            ParseResult::new_bad_slice(&["No choices matched", "Record has no fields"])
+        );
+    }
+}
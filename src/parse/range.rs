@@ -0,0 +1,181 @@
+use crate::parse::{ParseResult, Parser};
+use num::One;
+use std::marker::PhantomData;
+use std::ops::{RangeInclusive, Sub};
+
+pub struct RangeParser<P, T, PS, TS> {
+    value: P,
+    separator: PS,
+    exclusive: bool,
+    allow_single: bool,
+    spooky_ghost: PhantomData<(T, TS)>,
+}
+
+impl<P, T, PS, TS> RangeParser<P, T, PS, TS> {
+    /// Treat the end as exclusive, so `1..5` becomes `1..=4`. Ranges that end up empty fail.
+    #[inline]
+    pub fn exclusive(self) -> Self {
+        Self {
+            exclusive: true,
+            ..self
+        }
+    }
+
+    /// Accept a lone value without separator and end as the range `v..=v`.
+    #[inline]
+    pub fn allow_single(self) -> Self {
+        Self {
+            allow_single: true,
+            ..self
+        }
+    }
+}
+
+impl<P, T, PS, TS> Copy for RangeParser<P, T, PS, TS>
+where
+    P: Copy,
+    PS: Copy,
+{
+}
+
+impl<P, T, PS, TS> Clone for RangeParser<P, T, PS, TS>
+where
+    P: Clone,
+    PS: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            separator: self.separator.clone(),
+            exclusive: self.exclusive,
+            allow_single: self.allow_single,
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<'i, P, T, PS, TS> Parser<'i, RangeInclusive<T>> for RangeParser<P, T, PS, TS>
+where
+    P: Parser<'i, T>,
+    PS: Parser<'i, TS>,
+    T: Copy + PartialOrd + One + Sub<Output = T>,
+{
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, RangeInclusive<T>> {
+        let (start, input) = match self.value.parse(input) {
+            ParseResult::Good(v, new_input) => (v, new_input),
+            ParseResult::Bad(err) => return ParseResult::wrap_bad(err, "Range has no start"),
+        };
+
+        let input = match self.separator.parse(input) {
+            ParseResult::Good(_, new_input) => new_input,
+            ParseResult::Bad(err) => {
+                return if self.allow_single {
+                    ParseResult::Good(start..=start, input)
+                } else {
+                    ParseResult::wrap_bad(err, "Range has no separator")
+                };
+            }
+        };
+
+        let (end, input) = match self.value.parse(input) {
+            ParseResult::Good(v, new_input) => (v, new_input),
+            ParseResult::Bad(err) => return ParseResult::wrap_bad(err, "Range has no end"),
+        };
+
+        if self.exclusive {
+            if end <= start {
+                ParseResult::new_bad("Exclusive range is empty")
+            } else {
+                ParseResult::Good(start..=(end - T::one()), input)
+            }
+        } else if end < start {
+            ParseResult::new_bad("Range is empty")
+        } else {
+            ParseResult::Good(start..=end, input)
+        }
+    }
+}
+
+/// Parse an inclusive range of two values with a separator between, like `3-7` or `1..5`. Use
+/// `or` for the separator to accept several, and `exclusive`/`allow_single` to change how it is
+/// read. Ranges that would be empty, like `7-3`, fail.
+#[inline]
+pub fn range<'i, P, T, PS, TS>(value: P, separator: PS) -> RangeParser<P, T, PS, TS>
+where
+    P: Parser<'i, T>,
+    PS: Parser<'i, TS>,
+{
+    RangeParser {
+        value,
+        separator,
+        exclusive: false,
+        allow_single: false,
+        spooky_ghost: Default::default(),
+    }
+}
+
+/// Parse a list of ranges and single values like `1,2,3-9`. Single values become ranges with
+/// only one value in them.
+#[inline]
+pub fn range_list<'i, P, T, PS, TS, PD, TD>(
+    value: P,
+    separator: PS,
+    delimiter: PD,
+) -> impl Parser<'i, Vec<RangeInclusive<T>>>
+where
+    P: Parser<'i, T>,
+    PS: Parser<'i, TS>,
+    PD: Parser<'i, TD>,
+    T: Copy + PartialOrd + One + Sub<Output = T>,
+{
+    range(value, separator)
+        .allow_single()
+        .delimited_by(delimiter)
+        .repeat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{signed_int, unsigned_int};
+
+    #[test]
+    fn range_parses_ranges() {
+        let parser = range(unsigned_int::<u32>(), b"-".or(b".."));
+
+        assert_eq!(parser.parse(b"3-7 "), ParseResult::Good(3..=7, b" "));
+        assert_eq!(parser.parse(b"1..5"), ParseResult::Good(1..=5, b""));
+        assert_eq!(
+            parser.exclusive().parse(b"1..5"),
+            ParseResult::Good(1..=4, b"")
+        );
+        assert_eq!(
+            parser.exclusive().parse(b"5..5"),
+            ParseResult::new_bad("Exclusive range is empty")
+        );
+        assert_eq!(parser.parse(b"7-3"), ParseResult::new_bad("Range is empty"));
+        assert_eq!(parser.parse(b"5-5"), ParseResult::Good(5..=5, b""));
+        assert_eq!(
+            parser.parse(b"5,6"),
+            ParseResult::new_bad_slice(&["String does not match", "Range has no separator"])
+        );
+        assert_eq!(
+            parser.allow_single().parse(b"5,6"),
+            ParseResult::Good(5..=5, b",6")
+        );
+        assert_eq!(
+            b"x="
+                .and_instead(range(signed_int::<i32>(), b".."))
+                .parse(b"x=-3..-1"),
+            ParseResult::Good(-3..=-1, b"")
+        );
+    }
+
+    #[test]
+    fn range_list_parses_lists() {
+        assert_eq!(
+            range_list(unsigned_int::<u8>(), b'-', b',').parse(b"1,2,3-9,12\n"),
+            ParseResult::Good(vec![1..=1, 2..=2, 3..=9, 12..=12], b"\n")
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::parse::{ParseResult, Parser};
+use std::marker::PhantomData;
+
+pub struct FromFn<F, T> {
+    f: F,
+    spooky_ghost: PhantomData<T>,
+}
+
+impl<F, T> Copy for FromFn<F, T> where F: Copy {}
+
+impl<F, T> Clone for FromFn<F, T>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            f: self.f.clone(),
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<'i, F, T> Parser<'i, T> for FromFn<F, T>
+where
+    F: Fn(&'i [u8]) -> ParseResult<'i, T> + Copy,
+{
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, T> {
+        (self.f)(input)
+    }
+}
+
+/// Make a parser out of a function or closure. The closure must be copyable, so it can only
+/// capture other parsers and copyable values.
+#[inline]
+pub fn from_fn<'i, F, T>(f: F) -> FromFn<F, T>
+where
+    F: Fn(&'i [u8]) -> ParseResult<'i, T> + Copy,
+{
+    FromFn {
+        f,
+        spooky_ghost: Default::default(),
+    }
+}
+
+/// Parse a sequence of parsers where the results of the named ones are kept, and the rest are
+/// discarded. It returns a flat tuple of the named results, or a single value if there is only
+/// one of them.
+///
+/// ```ignore
+/// seq!(b"Valve ", name: n_bytes::<2>(), b" has flow rate=", rate: signed_int::<i32>())
+/// ```
+///
+/// Putting a struct name around it builds the struct from the named results instead.
+///
+/// ```ignore
+/// seq!(Valve { b"Valve ", name: n_bytes::<2>(), b" has flow rate=", rate: signed_int() })
+/// ```
+#[macro_export]
+macro_rules! seq {
+    (@munch $input:ident $out:tt [$($names:ident)*] [$($stmts:tt)*] $name:ident : $p:expr $(, $($rest:tt)*)?) => {
+        $crate::seq!(@munch $input $out [$($names)* $name] [
+            $($stmts)*
+            let ($name, $input) = match $crate::parse::Parser::parse(&$p, $input) {
+                $crate::parse::ParseResult::Good(v, new_input) => (v, new_input),
+                $crate::parse::ParseResult::Bad(err) => {
+                    return $crate::parse::ParseResult::wrap_bad(err, concat!("seq! failed at ", stringify!($name)));
+                }
+            };
+        ] $($($rest)*)?)
+    };
+    (@munch $input:ident $out:tt [$($names:ident)*] [$($stmts:tt)*] $p:expr $(, $($rest:tt)*)?) => {
+        $crate::seq!(@munch $input $out [$($names)*] [
+            $($stmts)*
+            let $input = match $crate::parse::Parser::parse(&$p, $input) {
+                $crate::parse::ParseResult::Good(_, new_input) => new_input,
+                $crate::parse::ParseResult::Bad(err) => {
+                    return $crate::parse::ParseResult::wrap_bad(err, concat!("seq! failed at ", stringify!($p)));
+                }
+            };
+        ] $($($rest)*)?)
+    };
+    (@munch $input:ident (struct $s:ident) [$($names:ident)*] [$($stmts:tt)*]) => {
+        $crate::parse::from_fn(move |$input| {
+            $($stmts)*
+            $crate::parse::ParseResult::Good($s { $($names),* }, $input)
+        })
+    };
+    (@munch $input:ident (tuple) [$name:ident] [$($stmts:tt)*]) => {
+        $crate::parse::from_fn(move |$input| {
+            $($stmts)*
+            $crate::parse::ParseResult::Good($name, $input)
+        })
+    };
+    (@munch $input:ident (tuple) [$($names:ident)*] [$($stmts:tt)*]) => {
+        $crate::parse::from_fn(move |$input| {
+            $($stmts)*
+            $crate::parse::ParseResult::Good(($($names,)*), $input)
+        })
+    };
+    ($s:ident { $($items:tt)* }) => {
+        $crate::seq!(@munch input (struct $s) [] [] $($items)*)
+    };
+    ($($items:tt)*) => {
+        $crate::seq!(@munch input (tuple) [] [] $($items)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::{n_bytes, signed_int, unsigned_int, ParseResult, Parser};
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct Valve {
+        name: [u8; 2],
+        rate: i32,
+        tunnels: Vec<[u8; 2]>,
+    }
+
+    #[test]
+    fn seq_builds_tuples() {
+        let parser = seq!(
+            b"Valve ",
+            name: n_bytes::<2>(),
+            b" has flow rate=",
+            rate: signed_int::<i32>(),
+        );
+
+        assert_eq!(
+            parser.parse(b"Valve AA has flow rate=20;"),
+            ParseResult::Good((*b"AA", 20), b";")
+        );
+        assert_eq!(
+            parser.parse(b"Valve AA has flow rate=x"),
+            ParseResult::new_bad_slice(&["SignedInt parsed non-number", "seq! failed at rate"])
+        );
+
+        let single = seq!(b'<', v: unsigned_int::<u32>(), b'>');
+        assert_eq!(single.parse(b"<42>"), ParseResult::Good(42, b""));
+    }
+
+    #[test]
+    fn seq_builds_structs() {
+        let parser = seq!(Valve {
+            b"Valve ",
+            name: n_bytes::<2>(),
+            b" has flow rate=",
+            rate: signed_int(),
+            b"; tunnel",
+            b" leads to valve ".or(b"s lead to valves "),
+            tunnels: n_bytes::<2>().delimited_by(b", ").repeat(),
+        });
+
+        assert_eq!(
+            parser
+                .delimited_by(b'\n')
+                .repeat::<Vec<_>>()
+                .parse(b"Valve BB has flow rate=13; tunnels lead to valves CC, AA\nValve HH has flow rate=22; tunnel leads to valve GG"),
+            ParseResult::Good(
+                vec![
+                    Valve {
+                        name: *b"BB",
+                        rate: 13,
+                        tunnels: vec![*b"CC", *b"AA"],
+                    },
+                    Valve {
+                        name: *b"HH",
+                        rate: 22,
+                        tunnels: vec![*b"GG"],
+                    },
+                ],
+                b""
+            )
+        );
+    }
+}
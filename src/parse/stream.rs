@@ -0,0 +1,334 @@
+use crate::parse::{ParseResult, Parser};
+use arrayvec::ArrayVec;
+use std::io;
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    /// A record failed to parse. The record number starts at 1.
+    Record {
+        record: usize,
+        errors: ArrayVec<&'static str, 4>,
+    },
+}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> Self {
+        StreamError::Io(err)
+    }
+}
+
+/// Reads one record at a time from a `BufRead`, so inputs larger than memory can be parsed.
+/// Records are separated by the delimiter, e.g. `b"\n"` for lines or `b"\n\n"` for groups, and
+/// only one record is kept in the buffer at a time.
+pub struct RecordReader<R> {
+    reader: R,
+    delimiter: &'static [u8],
+    buf: Vec<u8>,
+    record: usize,
+}
+
+impl<R> RecordReader<R>
+where
+    R: BufRead,
+{
+    pub fn new(reader: R, delimiter: &'static [u8]) -> Self {
+        assert!(!delimiter.is_empty(), "delimiter must not be empty");
+
+        Self {
+            reader,
+            delimiter,
+            buf: Vec::with_capacity(256),
+            record: 0,
+        }
+    }
+
+    /// The number of records read so far.
+    #[inline]
+    pub fn records_read(&self) -> usize {
+        self.record
+    }
+
+    /// Read the next record without its delimiter. A trailing delimiter at the end of the input
+    /// does not produce an empty record.
+    pub fn next_record(&mut self) -> io::Result<Option<&[u8]>> {
+        let last = self.delimiter[self.delimiter.len() - 1];
+        self.buf.clear();
+
+        loop {
+            if self.reader.read_until(last, &mut self.buf)? == 0 {
+                let trailing = (1..self.delimiter.len())
+                    .rev()
+                    .find(|len| self.buf.ends_with(&self.delimiter[..*len]))
+                    .unwrap_or(0);
+                self.buf.truncate(self.buf.len() - trailing);
+
+                if self.buf.is_empty() {
+                    return Ok(None);
+                }
+
+                break;
+            }
+
+            if self.buf.ends_with(self.delimiter) {
+                self.buf.truncate(self.buf.len() - self.delimiter.len());
+                break;
+            }
+        }
+
+        self.record += 1;
+        Ok(Some(&self.buf))
+    }
+
+    /// Parse the next record, which the parser must consume all of. The result may borrow from
+    /// the record, so it must be dropped before the next one is read.
+    pub fn parse_next<'r, P, T>(&'r mut self, parser: P) -> Option<Result<T, StreamError>>
+    where
+        P: Parser<'r, T>,
+    {
+        let record_number = self.record + 1;
+        let record = match self.next_record() {
+            Ok(Some(record)) => record,
+            Ok(None) => return None,
+            Err(err) => return Some(Err(StreamError::Io(err))),
+        };
+
+        Some(record_result(parser.parse(record), record_number))
+    }
+
+    /// Parse every record with the function, returning the results as an iterator. Since the
+    /// parsers are tied to the lifetime of their input, the function should build the parser and
+    /// run it on the record, e.g. `|record| unsigned_int().parse(record)`. The output must not
+    /// borrow from the record, use `parse_next` for that.
+    #[inline]
+    pub fn parse_iter<F, T>(self, f: F) -> StreamIterator<R, F, T>
+    where
+        F: for<'r> FnMut(&'r [u8]) -> ParseResult<'r, T>,
+    {
+        StreamIterator {
+            records: self,
+            f,
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+pub struct StreamIterator<R, F, T> {
+    records: RecordReader<R>,
+    f: F,
+    spooky_ghost: PhantomData<T>,
+}
+
+impl<R, F, T> Iterator for StreamIterator<R, F, T>
+where
+    R: BufRead,
+    F: for<'r> FnMut(&'r [u8]) -> ParseResult<'r, T>,
+{
+    type Item = Result<T, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record_number = self.records.record + 1;
+        let record = match self.records.next_record() {
+            Ok(Some(record)) => record,
+            Ok(None) => return None,
+            Err(err) => return Some(Err(StreamError::Io(err))),
+        };
+
+        Some(record_result((self.f)(record), record_number))
+    }
+}
+
+/// A record must be parsed to its end, or it counts as a failure.
+fn record_result<T>(res: ParseResult<T>, record: usize) -> Result<T, StreamError> {
+    match res {
+        ParseResult::Good(v, []) => Ok(v),
+        ParseResult::Good(..) => {
+            let mut errors = ArrayVec::new();
+            errors.push("Record was not fully parsed");
+            Err(StreamError::Record { record, errors })
+        }
+        ParseResult::Bad(errors) => Err(StreamError::Record { record, errors }),
+    }
+}
+
+/// Parse each record from the reader as it is read. See `RecordReader` for how records are
+/// split, and `RecordReader::parse_iter` for the function.
+#[inline]
+pub fn parse_stream<R, F, T>(reader: R, delimiter: &'static [u8], f: F) -> StreamIterator<R, F, T>
+where
+    R: BufRead,
+    F: for<'r> FnMut(&'r [u8]) -> ParseResult<'r, T>,
+{
+    RecordReader::new(reader, delimiter).parse_iter(f)
+}
+
+#[cfg(all(unix, feature = "mmap"))]
+pub use mapped::MappedFile;
+
+#[cfg(all(unix, feature = "mmap"))]
+mod mapped {
+    use std::fs::File;
+    use std::io;
+    use std::ops::Deref;
+    use std::os::unix::io::AsRawFd;
+    use std::path::Path;
+
+    /// A read-only memory-mapped file. It derefs to the file's bytes, so any parser can run
+    /// over it without reading the whole file into memory first. The file must not be changed
+    /// while it is mapped.
+    pub struct MappedFile {
+        ptr: *mut libc::c_void,
+        len: usize,
+    }
+
+    impl MappedFile {
+        pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            let file = File::open(path)?;
+            let len = file.metadata()?.len() as usize;
+            if len == 0 {
+                return Ok(Self {
+                    ptr: std::ptr::null_mut(),
+                    len,
+                });
+            }
+
+            // SAFETY: The mapping is private and read-only, and it is only handed out as a
+            // shared slice that cannot outlive self.
+            let ptr = unsafe {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    libc::PROT_READ,
+                    libc::MAP_PRIVATE,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(Self { ptr, len })
+        }
+    }
+
+    impl Deref for MappedFile {
+        type Target = [u8];
+
+        fn deref(&self) -> &[u8] {
+            if self.len == 0 {
+                return &[];
+            }
+
+            // SAFETY: ptr points to a live mapping of len bytes.
+            unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+        }
+    }
+
+    impl Drop for MappedFile {
+        fn drop(&mut self) {
+            if self.len > 0 {
+                // SAFETY: ptr and len are from a successful mmap call.
+                unsafe {
+                    libc::munmap(self.ptr, self.len);
+                }
+            }
+        }
+    }
+
+    unsafe impl Send for MappedFile {}
+    unsafe impl Sync for MappedFile {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{unsigned_int, word};
+    use std::io::{BufReader, Cursor};
+
+    #[test]
+    fn records_are_split_at_delimiters() {
+        let mut records = RecordReader::new(Cursor::new(b"a\nb\n\nc\nd\n".to_vec()), b"\n\n");
+
+        assert_eq!(records.next_record().unwrap(), Some(b"a\nb".as_slice()));
+        assert_eq!(records.next_record().unwrap(), Some(b"c\nd".as_slice()));
+        assert_eq!(records.next_record().unwrap(), None);
+        assert_eq!(records.records_read(), 2);
+    }
+
+    #[test]
+    fn parse_stream_parses_records() {
+        // A tiny buffer makes sure records spanning several refills work.
+        let input = BufReader::with_capacity(4, Cursor::new(b"12,345\n6,7\n89,10".to_vec()));
+        let res: Vec<(u32, u32)> = parse_stream(input, b"\n", |record| {
+            unsigned_int()
+                .and_discard(b',')
+                .and(unsigned_int())
+                .parse(record)
+        })
+        .map(|r| r.unwrap())
+        .collect();
+
+        assert_eq!(res, vec![(12, 345), (6, 7), (89, 10)]);
+
+        let mut iter = parse_stream(Cursor::new(b"1\nx\n3".to_vec()), b"\n", |record| {
+            unsigned_int::<u32>().parse(record)
+        });
+        assert!(matches!(iter.next(), Some(Ok(1))));
+        assert!(matches!(
+            iter.next(),
+            Some(Err(StreamError::Record { record: 2, .. }))
+        ));
+        assert!(matches!(iter.next(), Some(Ok(3))));
+        assert!(iter.next().is_none());
+
+        let mut iter = parse_stream(Cursor::new(b"1\n2x\n".to_vec()), b"\n", |record| {
+            unsigned_int::<u32>().parse(record)
+        });
+        assert!(matches!(iter.next(), Some(Ok(1))));
+        match iter.next() {
+            Some(Err(StreamError::Record { record, errors })) => {
+                assert_eq!(record, 2);
+                assert_eq!(errors.as_slice(), ["Record was not fully parsed"]);
+            }
+            _ => panic!("the leftover x should fail the record"),
+        }
+    }
+
+    #[test]
+    fn parse_next_can_borrow() {
+        let mut records = RecordReader::new(Cursor::new(b"abc\ndef\n".to_vec()), b"\n");
+        let mut total = 0;
+        while let Some(res) = records.parse_next(word()) {
+            total += res.unwrap().len();
+        }
+
+        assert_eq!(total, 6);
+
+        let mut records = RecordReader::new(Cursor::new(b"abc1\n".to_vec()), b"\n");
+        assert!(matches!(
+            records.parse_next(word()),
+            Some(Err(StreamError::Record { record: 1, .. }))
+        ));
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "mmap"))]
+    fn mapped_file_can_be_parsed() {
+        use crate::parse::parse_lines_par;
+
+        let path = std::env::temp_dir().join(format!("aoc_mapped_{}.txt", std::process::id()));
+        std::fs::write(&path, b"1\n2\n3\n").unwrap();
+
+        let mapped = MappedFile::open(&path).unwrap();
+        assert_eq!(
+            parse_lines_par(&mapped, unsigned_int::<u32>()),
+            Ok(vec![1, 2, 3])
+        );
+
+        drop(mapped);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
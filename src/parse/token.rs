@@ -0,0 +1,281 @@
+use crate::parse::{ParseResult, Parser};
+use std::marker::PhantomData;
+
+#[inline]
+fn is_ws(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+#[inline]
+fn skip_ws(input: &[u8]) -> &[u8] {
+    let len = input.iter().take_while(|v| is_ws(**v)).count();
+    &input[len..]
+}
+
+#[inline]
+fn is_ident(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+#[derive(Copy, Clone)]
+struct Whitespace;
+
+impl<'i> Parser<'i, &'i [u8]> for Whitespace {
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, &'i [u8]> {
+        let rest = skip_ws(input);
+        ParseResult::Good(&input[..input.len() - rest.len()], rest)
+    }
+}
+
+struct Token<P, T> {
+    parser: P,
+    spooky_ghost: PhantomData<T>,
+}
+
+impl<P, T> Copy for Token<P, T> where P: Copy {}
+
+impl<P, T> Clone for Token<P, T>
+where
+    P: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<'i, P, T> Parser<'i, T> for Token<P, T>
+where
+    P: Parser<'i, T>,
+{
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, T> {
+        match self.parser.parse(skip_ws(input)) {
+            ParseResult::Good(v, new_input) => ParseResult::Good(v, skip_ws(new_input)),
+            ParseResult::Bad(err) => ParseResult::wrap_bad(err, "Token failed"),
+        }
+    }
+
+    #[inline]
+    fn parse_at_index(&self, input: &'i [u8], index: usize) -> ParseResult<'i, T> {
+        match self.parser.parse_at_index(skip_ws(input), index) {
+            ParseResult::Good(v, new_input) => ParseResult::Good(v, skip_ws(new_input)),
+            ParseResult::Bad(err) => ParseResult::wrap_bad(err, "Token failed"),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Keyword<'s>(&'s [u8]);
+
+impl<'i, 's> Parser<'i, &'i [u8]> for Keyword<'s> {
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, &'i [u8]> {
+        let start = skip_ws(input);
+        let mut current = start;
+        let mut end = 0;
+
+        for word in self.0.split(|v| is_ws(*v)).filter(|w| !w.is_empty()) {
+            current = skip_ws(current);
+            if !current.starts_with(word) {
+                return ParseResult::new_bad("Keyword does not match");
+            }
+
+            current = &current[word.len()..];
+            end = start.len() - current.len();
+        }
+
+        // `Card` should not match the start of `Cardinal`.
+        if let (Some(last), Some(next)) = (start[..end].last(), current.first()) {
+            if is_ident(*last) && is_ident(*next) {
+                return ParseResult::new_bad("Keyword is part of a longer word");
+            }
+        }
+
+        ParseResult::Good(&start[..end], skip_ws(current))
+    }
+}
+
+struct TokensMode<P, T> {
+    parser: P,
+    spooky_ghost: PhantomData<T>,
+}
+
+impl<P, T> Copy for TokensMode<P, T> where P: Copy {}
+
+impl<P, T> Clone for TokensMode<P, T>
+where
+    P: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<'i, P, T> Parser<'i, T> for TokensMode<P, T>
+where
+    P: Parser<'i, T>,
+{
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, T> {
+        match self.parser.parse(skip_ws(input)) {
+            ParseResult::Good(v, new_input) => {
+                let new_input = skip_ws(new_input);
+                match new_input.first() {
+                    None | Some(b'\n') | Some(b'\r') => ParseResult::Good(v, new_input),
+                    Some(_) => {
+                        ParseResult::new_bad("Tokens mode did not reach the end of the line")
+                    }
+                }
+            }
+            ParseResult::Bad(err) => ParseResult::wrap_bad(err, "Tokens mode failed"),
+        }
+    }
+}
+
+/// Parse zero or more spaces and tabs. This never fails, and it does not eat newlines since
+/// those usually separate the records.
+#[inline]
+pub fn ws<'i>() -> impl Parser<'i, &'i [u8]> {
+    Whitespace
+}
+
+/// Parse this as a lexeme, skipping any horizontal whitespace before and after it. Put this on
+/// delimiters (e.g. `p.delimited_by(token(b','))`) to accept `1,2`, `1, 2` and `1 ,  2` alike.
+#[inline]
+pub fn token<'i, P, T>(parser: P) -> impl Parser<'i, T>
+where
+    P: Parser<'i, T>,
+{
+    Token {
+        parser,
+        spooky_ghost: Default::default(),
+    }
+}
+
+/// Match a literal as a lexeme, where every run of whitespace in the literal will match any
+/// amount of horizontal whitespace in the input. If it ends in a letter, digit or `_`, the input
+/// can't continue with one. The matched span is returned without the surrounding whitespace.
+#[inline]
+pub fn keyword<'i, 's: 'i>(literal: &'s [u8]) -> impl Parser<'i, &'i [u8]> {
+    Keyword(literal)
+}
+
+/// Parse a whole line in token mode, where the parser is built from `token` and `keyword`. The
+/// whitespace around it is skipped, and it fails if anything but the newline is left on the line
+/// rather than leaving it for the next parser.
+#[inline]
+pub fn tokens_mode<'i, P, T>(parser: P) -> impl Parser<'i, T>
+where
+    P: Parser<'i, T>,
+{
+    TokensMode {
+        parser,
+        spooky_ghost: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{signed_int, unsigned_int};
+
+    #[test]
+    fn token_skips_surrounding_whitespace() {
+        assert_eq!(
+            ws().parse(b" \t x"),
+            ParseResult::Good(b" \t ".as_slice(), b"x")
+        );
+        assert_eq!(ws().parse(b"x"), ParseResult::Good(b"".as_slice(), b"x"));
+        assert_eq!(
+            ws().parse(b"\nx"),
+            ParseResult::Good(b"".as_slice(), b"\nx")
+        );
+
+        assert_eq!(
+            token(unsigned_int::<u32>()).parse(b"  42\t, 43"),
+            ParseResult::Good(42, b", 43")
+        );
+        assert_eq!(
+            token(b',').parse(b"x"),
+            ParseResult::new_bad_slice(&["u8 not matched", "Token failed"])
+        );
+
+        let parser = signed_int::<i64>()
+            .delimited_by(token(b','))
+            .repeat_n::<(_, _, _)>(3)
+            .delimited_by(token(b'@'))
+            .repeat_n::<(_, _)>(2);
+
+        assert_eq!(
+            parser.parse(b"19, 13, 30 @ -2, 1, -2"),
+            parser.parse(b"19,13 ,30@ -2,  1,\t-2"),
+        );
+        assert_eq!(
+            parser.parse(b"19, 13, 30 @ -2,  1, -2\n"),
+            ParseResult::Good(((19, 13, 30), (-2, 1, -2)), b"\n")
+        );
+    }
+
+    #[test]
+    fn keyword_matches_loose_literals() {
+        let parser = keyword(b" = (");
+
+        assert_eq!(
+            parser.parse(b"  =  (BBB"),
+            ParseResult::Good(b"=  (".as_slice(), b"BBB")
+        );
+        assert_eq!(
+            parser.parse(b"=(BBB"),
+            ParseResult::Good(b"=(".as_slice(), b"BBB")
+        );
+        assert_eq!(
+            parser.parse(b"= [BBB"),
+            ParseResult::new_bad("Keyword does not match")
+        );
+        assert_eq!(
+            keyword(b"Card")
+                .and_instead(unsigned_int::<u32>())
+                .parse(b"Card   12:"),
+            ParseResult::Good(12, b":")
+        );
+        assert_eq!(
+            keyword(b"Card").parse(b"Cardinal 12"),
+            ParseResult::new_bad("Keyword is part of a longer word")
+        );
+        assert_eq!(
+            keyword(b"Card").parse(b"Card"),
+            ParseResult::Good(b"Card".as_slice(), b"")
+        );
+    }
+
+    #[test]
+    fn tokens_mode_parses_whole_lines() {
+        let parser = tokens_mode(
+            keyword(b"Card")
+                .and_instead(token(unsigned_int::<u32>()))
+                .and_discard(token(b':'))
+                .and(token(unsigned_int::<u32>()).repeat::<Vec<_>>()),
+        );
+
+        assert_eq!(
+            parser.parse(b"Card 1: 41 48\nCard 2"),
+            ParseResult::Good((1, vec![41, 48]), b"\nCard 2")
+        );
+        assert_eq!(
+            parser.parse(b"  Card   1 :41   48  "),
+            ParseResult::Good((1, vec![41, 48]), b"")
+        );
+        assert_eq!(
+            parser.parse(b"Card 1: 41 48 | 83\n"),
+            ParseResult::new_bad("Tokens mode did not reach the end of the line")
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use crate::parse::{ParseResult, Parser};
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+
+#[cfg(feature = "parse-trace")]
+use std::cell::RefCell;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TraceEventKind {
+    Enter,
+    Exit { good: bool, consumed: usize },
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TraceEvent {
+    pub name: &'static str,
+    pub depth: usize,
+    pub offset: usize,
+    pub kind: TraceEventKind,
+}
+
+/// The events recorded by `trace_parse`. Display renders it as an indented tree with one line
+/// per traced parser.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Trace {
+    pub events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    pub fn render(&self) -> String {
+        let mut lines: Vec<String> = Vec::with_capacity(self.events.len() / 2);
+        let mut stack = Vec::with_capacity(16);
+
+        for event in self.events.iter() {
+            match event.kind {
+                TraceEventKind::Enter => {
+                    stack.push(lines.len());
+                    lines.push(format!(
+                        "{}{} @{}",
+                        "  ".repeat(event.depth),
+                        event.name,
+                        event.offset
+                    ));
+                }
+                TraceEventKind::Exit { good, consumed } => {
+                    if let Some(line) = stack.pop().and_then(|i| lines.get_mut(i)) {
+                        if good {
+                            line.push_str(&format!(" ok +{}", consumed));
+                        } else {
+                            line.push_str(" failed");
+                        }
+                    }
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+#[cfg(feature = "parse-trace")]
+struct TraceSink {
+    base: usize,
+    depth: usize,
+    events: Vec<TraceEvent>,
+}
+
+#[cfg(feature = "parse-trace")]
+thread_local! {
+    static TRACE_SINK: RefCell<Option<TraceSink>> = const { RefCell::new(None) };
+}
+
+pub struct Traced<P, T> {
+    parser: P,
+    name: &'static str,
+    spooky_ghost: PhantomData<T>,
+}
+
+impl<P, T> Traced<P, T> {
+    #[inline]
+    pub(crate) fn new(parser: P, name: &'static str) -> Self {
+        Self {
+            parser,
+            name,
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<P, T> Copy for Traced<P, T> where P: Copy {}
+
+impl<P, T> Clone for Traced<P, T>
+where
+    P: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+            name: self.name,
+            spooky_ghost: Default::default(),
+        }
+    }
+}
+
+impl<P, T> Traced<P, T> {
+    #[cfg(feature = "parse-trace")]
+    fn trace<'i, F>(&self, input: &'i [u8], f: F) -> ParseResult<'i, T>
+    where
+        F: FnOnce() -> ParseResult<'i, T>,
+    {
+        let ptr = input.as_ptr() as usize;
+        let entered = TRACE_SINK.with(|sink| match sink.borrow_mut().as_mut() {
+            Some(sink) => {
+                sink.events.push(TraceEvent {
+                    name: self.name,
+                    depth: sink.depth,
+                    offset: ptr.wrapping_sub(sink.base),
+                    kind: TraceEventKind::Enter,
+                });
+                sink.depth += 1;
+                true
+            }
+            None => false,
+        });
+        if !entered {
+            return f();
+        }
+
+        let res = f();
+
+        TRACE_SINK.with(|sink| {
+            if let Some(sink) = sink.borrow_mut().as_mut() {
+                sink.depth -= 1;
+                sink.events.push(TraceEvent {
+                    name: self.name,
+                    depth: sink.depth,
+                    offset: ptr.wrapping_sub(sink.base),
+                    kind: TraceEventKind::Exit {
+                        good: matches!(res, ParseResult::Good(..)),
+                        consumed: match &res {
+                            ParseResult::Good(_, new_input) => input.len() - new_input.len(),
+                            ParseResult::Bad(_) => 0,
+                        },
+                    },
+                });
+            }
+        });
+
+        res
+    }
+
+    #[cfg(not(feature = "parse-trace"))]
+    #[inline(always)]
+    fn trace<'i, F>(&self, _input: &'i [u8], f: F) -> ParseResult<'i, T>
+    where
+        F: FnOnce() -> ParseResult<'i, T>,
+    {
+        f()
+    }
+}
+
+impl<'i, P, T> Parser<'i, T> for Traced<P, T>
+where
+    P: Parser<'i, T>,
+{
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, T> {
+        self.trace(input, || self.parser.parse(input))
+    }
+
+    #[inline]
+    fn parse_at_index(&self, input: &'i [u8], index: usize) -> ParseResult<'i, T> {
+        self.trace(input, || self.parser.parse_at_index(input, index))
+    }
+}
+
+/// Run the parser while recording the events of all `traced` parsers within it on this thread.
+/// The offsets are relative to the start of this input. Without the `parse-trace` feature, the
+/// trace will always be empty.
+pub fn trace_parse<'i, P, T>(parser: P, input: &'i [u8]) -> (ParseResult<'i, T>, Trace)
+where
+    P: Parser<'i, T>,
+{
+    #[cfg(feature = "parse-trace")]
+    {
+        let previous = TRACE_SINK.with(|sink| {
+            sink.borrow_mut().replace(TraceSink {
+                base: input.as_ptr() as usize,
+                depth: 0,
+                events: Vec::with_capacity(64),
+            })
+        });
+
+        let res = parser.parse(input);
+
+        let sink = TRACE_SINK.with(|sink| std::mem::replace(&mut *sink.borrow_mut(), previous));
+        let events = sink.map(|s| s.events).unwrap_or_default();
+
+        (res, Trace { events })
+    }
+
+    #[cfg(not(feature = "parse-trace"))]
+    {
+        (parser.parse(input), Trace::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::unsigned_int;
+
+    #[test]
+    fn traced_does_not_change_the_result() {
+        let parser = unsigned_int::<u32>()
+            .traced("number")
+            .delimited_by(b',')
+            .repeat::<Vec<_>>()
+            .traced("list");
+
+        assert_eq!(
+            parser.parse(b"1,2,3;"),
+            ParseResult::Good(vec![1, 2, 3], b";")
+        );
+        assert_eq!(
+            trace_parse(parser, b"1,2,3;").0,
+            ParseResult::Good(vec![1, 2, 3], b";")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "parse-trace")]
+    fn trace_renders_a_tree() {
+        let parser = b"x="
+            .and_instead(unsigned_int::<u32>().traced("x"))
+            .and_discard(b',')
+            .and(b"y=".and_instead(unsigned_int::<u32>().traced("y")))
+            .traced("point")
+            .delimited_by(b' ')
+            .repeat::<Vec<_>>()
+            .traced("points");
+
+        let (res, trace) = trace_parse(parser, b"x=1,y=22 x=3,y=z");
+
+        assert_eq!(res, ParseResult::Good(vec![(1, 22)], b" x=3,y=z"));
+        assert_eq!(
+            trace.events[0],
+            TraceEvent {
+                name: "points",
+                depth: 0,
+                offset: 0,
+                kind: TraceEventKind::Enter
+            }
+        );
+        assert_eq!(
+            trace.render(),
+            "points @0 ok +8
+  point @0 ok +8
+    x @2 ok +1
+    y @6 ok +2
+  point @9 failed
+    x @11 ok +1
+    y @15 failed"
+        );
+    }
+}
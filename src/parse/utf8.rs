@@ -0,0 +1,263 @@
+use crate::parse::{ParseResult, Parser};
+use crate::utils::scan::find_subslice;
+use std::str::from_utf8;
+
+/// Decode the first char in the input, returning it with its length in bytes.
+#[inline]
+fn decode_char(input: &[u8]) -> Option<(char, usize)> {
+    let len = match *input.first()? {
+        0x00..=0x7F => 1,
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return None,
+    };
+
+    from_utf8(input.get(..len)?)
+        .ok()
+        .and_then(|s| s.chars().next())
+        .map(|c| (c, len))
+}
+
+impl<'i> Parser<'i, char> for char {
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, char> {
+        let mut buf = [0u8; 4];
+        let encoded = self.encode_utf8(&mut buf).as_bytes();
+
+        if input.starts_with(encoded) {
+            ParseResult::Good(*self, &input[encoded.len()..])
+        } else {
+            ParseResult::new_bad("char not matched")
+        }
+    }
+
+    #[inline]
+    fn first_parsable_in(&self, input: &'i [u8]) -> ParseResult<'i, (char, usize)> {
+        let mut buf = [0u8; 4];
+        let encoded = self.encode_utf8(&mut buf).as_bytes();
+
+        match find_subslice(input, encoded) {
+            Some(index) => ParseResult::Good((*self, index), &input[index + encoded.len()..]),
+            None => ParseResult::new_bad("char not found in input"),
+        }
+    }
+}
+
+impl<'i> Parser<'i, &'i str> for &str {
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, &'i str> {
+        if input.starts_with(self.as_bytes()) {
+            let (head, tail) = input.split_at(self.len());
+            match from_utf8(head) {
+                Ok(head) => ParseResult::Good(head, tail),
+                Err(_) => ParseResult::new_bad("str literal is not valid UTF-8"),
+            }
+        } else {
+            ParseResult::new_bad("str does not match")
+        }
+    }
+
+    #[inline]
+    fn first_parsable_in(&self, input: &'i [u8]) -> ParseResult<'i, (&'i str, usize)> {
+        match find_subslice(input, self.as_bytes()) {
+            Some(index) => match from_utf8(&input[index..index + self.len()]) {
+                Ok(head) => ParseResult::Good((head, index), &input[index + self.len()..]),
+                Err(_) => ParseResult::new_bad("str literal is not valid UTF-8"),
+            },
+            None => ParseResult::new_bad("str not found in input"),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct AnyChar;
+
+impl<'i> Parser<'i, char> for AnyChar {
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, char> {
+        if input.is_empty() {
+            return ParseResult::new_bad("Empty input");
+        }
+
+        match decode_char(input) {
+            Some((c, len)) => ParseResult::Good(c, &input[len..]),
+            None => ParseResult::new_bad("Invalid UTF-8"),
+        }
+    }
+}
+
+struct TakeWhileChar<F>(F)
+where
+    F: Fn(char) -> bool;
+
+impl<F> Clone for TakeWhileChar<F>
+where
+    F: Fn(char) -> bool + Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<F> Copy for TakeWhileChar<F> where F: Fn(char) -> bool + Copy {}
+
+impl<'i, F> Parser<'i, &'i str> for TakeWhileChar<F>
+where
+    F: Fn(char) -> bool + Copy,
+{
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, &'i str> {
+        let mut len = 0;
+        while let Some((c, char_len)) = decode_char(&input[len..]) {
+            if !(self.0)(c) {
+                break;
+            }
+
+            len += char_len;
+        }
+
+        if len > 0 {
+            match from_utf8(&input[..len]) {
+                Ok(s) => ParseResult::Good(s, &input[len..]),
+                Err(_) => ParseResult::new_bad("Invalid UTF-8"),
+            }
+        } else {
+            ParseResult::new_bad("nothing matched TakeWhileChar")
+        }
+    }
+}
+
+pub struct AsStr<P> {
+    parser: P,
+}
+
+impl<P> Copy for AsStr<P> where P: Copy {}
+
+impl<P> Clone for AsStr<P>
+where
+    P: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            parser: self.parser.clone(),
+        }
+    }
+}
+
+impl<'i, P> Parser<'i, &'i str> for AsStr<P>
+where
+    P: Parser<'i, &'i [u8]>,
+{
+    #[inline]
+    fn parse(&self, input: &'i [u8]) -> ParseResult<'i, &'i str> {
+        match self.parser.parse(input) {
+            ParseResult::Good(v, new_input) => match from_utf8(v) {
+                Ok(s) => ParseResult::Good(s, new_input),
+                Err(_) => ParseResult::new_bad("AsStr got invalid UTF-8"),
+            },
+            ParseResult::Bad(err) => ParseResult::Bad(err),
+        }
+    }
+
+    #[inline]
+    fn parse_at_index(&self, input: &'i [u8], index: usize) -> ParseResult<'i, &'i str> {
+        match self.parser.parse_at_index(input, index) {
+            ParseResult::Good(v, new_input) => match from_utf8(v) {
+                Ok(s) => ParseResult::Good(s, new_input),
+                Err(_) => ParseResult::new_bad("AsStr got invalid UTF-8"),
+            },
+            ParseResult::Bad(err) => ParseResult::Bad(err),
+        }
+    }
+}
+
+/// Parse one UTF-8 encoded char.
+#[inline]
+pub fn any_char<'i>() -> impl Parser<'i, char> {
+    AnyChar
+}
+
+/// Like `word`, but it takes any Unicode alphabetic chars.
+#[inline]
+pub fn word_str<'i>() -> impl Parser<'i, &'i str> {
+    TakeWhileChar(char::is_alphabetic)
+}
+
+/// Like `take_while`, but for chars.
+#[inline]
+pub fn take_while_char<'i, F>(f: F) -> impl Parser<'i, &'i str>
+where
+    F: Fn(char) -> bool + Copy,
+{
+    TakeWhileChar(f)
+}
+
+/// Turn the byte slice from this parser into a `&str`, failing if it is not valid UTF-8. The
+/// str still points into the input.
+#[inline]
+pub fn as_str<'i, P>(parser: P) -> AsStr<P>
+where
+    P: Parser<'i, &'i [u8]>,
+{
+    AsStr { parser }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{line, unsigned_int};
+
+    #[test]
+    fn chars_and_strs_match() {
+        assert_eq!(
+            any_char().parse("ær".as_bytes()),
+            ParseResult::Good('æ', b"r")
+        );
+        assert_eq!(any_char().parse(b"ab"), ParseResult::Good('a', b"b"));
+        assert_eq!(
+            any_char().parse(&[0xE2, 0x82]),
+            ParseResult::new_bad("Invalid UTF-8")
+        );
+        assert_eq!('→'.parse_str("→ 4"), ParseResult::Good('→', b" 4"));
+        assert_eq!(
+            '→'.first_parsable_in("a → b".as_bytes()),
+            ParseResult::Good(('→', 2), b" b")
+        );
+        assert_eq!(
+            "Grüße"
+                .and_instead(unsigned_int::<u32>())
+                .parse_str("Grüße42"),
+            ParseResult::Good(42, b"")
+        );
+        assert_eq!(
+            "Grüße".parse_str("Gruße"),
+            ParseResult::new_bad("str does not match")
+        );
+    }
+
+    #[test]
+    fn strs_point_into_the_input() {
+        let input = "Blåbærsyltetøy: 12\nÆøå";
+
+        let (name, rest) = match word_str().parse_str(input) {
+            ParseResult::Good(name, rest) => (name, rest),
+            ParseResult::Bad(err) => panic!("{:?}", err),
+        };
+        assert_eq!(name, "Blåbærsyltetøy");
+        assert_eq!(name.as_ptr(), input.as_ptr());
+        assert_eq!(rest, ": 12\nÆøå".as_bytes());
+
+        assert_eq!(
+            take_while_char(|c| c != '\n').parse_str("æ ø\nå"),
+            ParseResult::Good("æ ø", b"\n\xC3\xA5")
+        );
+        assert_eq!(
+            as_str(line()).repeat::<Vec<_>>().parse_str("én\nto\ntré"),
+            ParseResult::Good(vec!["én", "to", "tré"], b"")
+        );
+        assert_eq!(
+            as_str(line()).parse(&[0xC3, b'\n']),
+            ParseResult::new_bad("AsStr got invalid UTF-8")
+        );
+    }
+}
@@ -53,19 +53,28 @@ where
         }
     }
 
+    /// Only the positions the vanguard finds are tried, so a vanguard with a fast
+    /// `first_parsable_in` (e.g. a byte or a literal) lets the scan skip ahead.
     #[inline]
     fn first_parsable_in(&self, input: &'i [u8]) -> ParseResult<'i, (T, usize)> {
-        match self.vanguard_parser.first_parsable_in(input) {
-            ParseResult::Good((_, pos), _) => {
-                match self.value_parser.first_parsable_in(&input[pos..]) {
-                    ParseResult::Good((v, pos2), new_input) => {
-                        ParseResult::Good((v, pos + pos2), new_input)
+        let mut offset = 0;
+        while offset < input.len() {
+            match self.vanguard_parser.first_parsable_in(&input[offset..]) {
+                ParseResult::Good((_, pos), _) => {
+                    offset += pos;
+                    if let ParseResult::Good(v, new_input) =
+                        self.value_parser.parse(&input[offset..])
+                    {
+                        return ParseResult::Good((v, offset), new_input);
                     }
-                    bad_result => bad_result,
+
+                    offset += 1;
                 }
+                ParseResult::Bad(err) => return ParseResult::wrap_bad(err, "Vanguard failed"),
             }
-            ParseResult::Bad(err) => ParseResult::wrap_bad(err, "Vanguard failed"),
         }
+
+        ParseResult::new_bad("Vanguard found nothing parsable")
     }
 }
 
@@ -83,6 +92,11 @@ mod tests {
             vanguard.first_parsable_in(b"There's a number in this text, it is not 27, but 42!"),
             ParseResult::Good((42, 49), b"!"),
         );
+        assert_eq!(
+            Vanguard::new(unsigned_int::<u32>().only_if(|v| *v > 100), b'4')
+                .first_parsable_in(b"Not 4, not 14, but 404 and then 42!"),
+            ParseResult::Good((404, 19), b" and then 42!"),
+        );
         assert_eq!(vanguard.parse(b"42!"), ParseResult::Good(42, b"!"),);
 
         assert_eq!(vanguard.parse(b"42!"), without_vanguard.parse(b"42!"));
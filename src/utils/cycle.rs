@@ -0,0 +1,213 @@
+use rustc_hash::FxHashMap;
+use std::hash::Hash;
+
+/// Find the cycle that the states reach by repeatedly applying `step` to `initial`, with Brent's
+/// algorithm. It returns the number of states before the cycle, and the length of the cycle.
+///
+/// It only keeps two states around, but it also steps through the sequence more than once. It
+/// never returns if the sequence has no cycle.
+pub fn find_cycle<T, F>(initial: T, mut step: F) -> (usize, usize)
+where
+    T: Clone + Eq,
+    F: FnMut(&mut T),
+{
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = initial.clone();
+    step(&mut hare);
+
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+
+        step(&mut hare);
+        len += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..len {
+        step(&mut hare);
+    }
+
+    let mut prefix = 0;
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        prefix += 1;
+    }
+
+    (prefix, len)
+}
+
+/// Like `find_cycle`, but the `key` of every state is stored in a hash map until one repeats.
+/// The sequence is only stepped through once, and the key can be a smaller projection of the
+/// state as long as it tells them apart.
+pub fn find_cycle_hashed<T, K, F, KF>(initial: T, mut step: F, key: KF) -> (usize, usize)
+where
+    K: Hash + Eq,
+    F: FnMut(&mut T),
+    KF: Fn(&T) -> K,
+{
+    let mut seen = FxHashMap::default();
+    let mut state = initial;
+
+    for i in 0.. {
+        if let Some(prev) = seen.insert(key(&state), i) {
+            return (prev, i - prev);
+        }
+
+        step(&mut state);
+    }
+
+    unreachable!()
+}
+
+/// Get the state after `n` steps, skipping over the full cycles once a state repeats.
+pub fn nth_state<T, F>(initial: T, step: F, n: usize) -> T
+where
+    T: Clone + Hash + Eq,
+    F: FnMut(&mut T),
+{
+    nth_state_by_key(initial, step, T::clone, n)
+}
+
+/// Like `nth_state`, but with a projection of the state as the key for finding repeats. The key
+/// must decide every later state, since the parts of the state outside it are not stepped
+/// through the skipped cycles. Use `nth_value_by_key` if it only decides the values.
+pub fn nth_state_by_key<T, K, F, KF>(initial: T, mut step: F, key: KF, n: usize) -> T
+where
+    K: Hash + Eq,
+    F: FnMut(&mut T),
+    KF: Fn(&T) -> K,
+{
+    let mut seen = FxHashMap::default();
+    let mut state = initial;
+
+    let mut i = 0;
+    while i < n {
+        if let Some(prev) = seen.insert(key(&state), i) {
+            i = n - ((n - i) % (i - prev));
+            break;
+        }
+
+        step(&mut state);
+        i += 1;
+    }
+
+    for _ in i..n {
+        step(&mut state);
+    }
+
+    state
+}
+
+/// Get a value of the state after `n` steps. The values are kept as the states are stepped
+/// through, so the answer is looked up from them once a state repeats.
+pub fn nth_value<T, V, F, VF>(initial: T, step: F, value: VF, n: usize) -> V
+where
+    T: Clone + Hash + Eq,
+    F: FnMut(&mut T),
+    VF: Fn(&T) -> V,
+{
+    nth_value_by_key(initial, step, T::clone, value, n)
+}
+
+/// Like `nth_value`, but with a projection of the state as the key for finding repeats.
+pub fn nth_value_by_key<T, K, V, F, KF, VF>(
+    initial: T,
+    mut step: F,
+    key: KF,
+    value: VF,
+    n: usize,
+) -> V
+where
+    K: Hash + Eq,
+    F: FnMut(&mut T),
+    KF: Fn(&T) -> K,
+    VF: Fn(&T) -> V,
+{
+    let mut seen = FxHashMap::default();
+    let mut values = Vec::new();
+    let mut state = initial;
+
+    for i in 0.. {
+        if let Some(prev) = seen.insert(key(&state), i) {
+            values.truncate(prev + ((n - prev) % (i - prev)) + 1);
+            break;
+        }
+
+        values.push(value(&state));
+        if i == n {
+            break;
+        }
+
+        step(&mut state);
+    }
+
+    values.pop().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, 2, 5, 26, 40, 33, 12, 47, 5, 26, ...
+    fn step(v: &mut u32) {
+        *v = (*v * *v + 1) % 49;
+    }
+
+    #[test]
+    fn cycles_are_found() {
+        assert_eq!(find_cycle(0, step), (3, 6));
+        assert_eq!(find_cycle_hashed(0, step, |v| *v), (3, 6));
+        assert_eq!(find_cycle(26, step), (0, 6));
+        assert_eq!(find_cycle_hashed(5u32, |v| *v = 7 - *v, |v| *v), (0, 2));
+    }
+
+    #[test]
+    fn nth_state_skips_cycles() {
+        assert_eq!(nth_state(0, step, 0), 0);
+        assert_eq!(nth_state(0, step, 8), 47);
+        assert_eq!(nth_state(0, step, 12), 33);
+        assert_eq!(nth_state(0, step, 1_000_000_000), 26);
+        assert_eq!(nth_state(0, step, 1_000_000_001), 40);
+    }
+
+    #[test]
+    fn nth_value_skips_cycles() {
+        assert_eq!(nth_value(0, step, |v| v % 10, 0), 0);
+        assert_eq!(nth_value(0, step, |v| v % 10, 12), 3);
+        assert_eq!(nth_value(0, step, |v| v % 10, 1_000_000_003), 2);
+    }
+
+    #[test]
+    fn keys_can_be_projections() {
+        // Only the position is part of the key, which is enough for the values.
+        let walk = |(pos, count): &mut (u32, u64)| {
+            *pos = (*pos + 3) % 10;
+            *count += 1;
+        };
+
+        assert_eq!(find_cycle_hashed((4, 0), walk, |s| s.0), (0, 10));
+        assert_eq!(nth_value_by_key((4, 0), walk, |s| s.0, |s| s.0 * 2, 13), 6);
+    }
+
+    #[test]
+    fn nth_state_can_use_a_smaller_key() {
+        // The digit only ever follows the position, so the position decides the state.
+        let walk = |(pos, digit): &mut (u32, u8)| {
+            *pos = (*pos + 3) % 10;
+            *digit = b'0' + *pos as u8;
+        };
+
+        assert_eq!(
+            nth_state_by_key((4, b'4'), walk, |s| s.0, 1_000_000_007),
+            (5, b'5')
+        );
+    }
+}
@@ -1 +1,3 @@
+pub mod cycle;
 pub mod gather_target;
+pub mod scan;
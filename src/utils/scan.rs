@@ -0,0 +1,161 @@
+use std::simd::cmp::SimdPartialEq;
+use std::simd::Simd;
+
+const LANES: usize = 32;
+
+type Chunk = Simd<u8, LANES>;
+
+/// Find the first position of the byte in the haystack.
+#[inline]
+pub fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    let splat = Chunk::splat(needle);
+    let (chunks, rest) = haystack.as_chunks::<LANES>();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let mask = Chunk::from_array(*chunk).simd_eq(splat).to_bitmask();
+        if mask != 0 {
+            return Some((i * LANES) + mask.trailing_zeros() as usize);
+        }
+    }
+
+    let offset = chunks.len() * LANES;
+    rest.iter().position(|v| *v == needle).map(|p| offset + p)
+}
+
+/// Find the first position of any of the bytes in the haystack.
+#[inline]
+pub fn find_either_byte<const N: usize>(haystack: &[u8], needles: &[u8; N]) -> Option<usize> {
+    let splats = needles.map(Chunk::splat);
+    let (chunks, rest) = haystack.as_chunks::<LANES>();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let chunk = Chunk::from_array(*chunk);
+        let mask = splats
+            .iter()
+            .fold(0, |acc, splat| acc | chunk.simd_eq(*splat).to_bitmask());
+        if mask != 0 {
+            return Some((i * LANES) + mask.trailing_zeros() as usize);
+        }
+    }
+
+    let offset = chunks.len() * LANES;
+    rest.iter()
+        .position(|v| needles.contains(v))
+        .map(|p| offset + p)
+}
+
+/// Find the first position of the needle in the haystack. Candidates are found by matching the
+/// first and last byte of the needle for a whole chunk of positions at once, and only those are
+/// compared in full.
+#[inline]
+pub fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle.len() {
+        0 => return Some(0),
+        1 => return find_byte(haystack, needle[0]),
+        _ => {}
+    }
+    if haystack.len() < needle.len() {
+        return None;
+    }
+
+    let last = needle.len() - 1;
+    let first_splat = Chunk::splat(needle[0]);
+    let last_splat = Chunk::splat(needle[last]);
+
+    let mut i = 0;
+    while i + last + LANES <= haystack.len() {
+        let firsts = Chunk::from_slice(&haystack[i..i + LANES]);
+        let lasts = Chunk::from_slice(&haystack[i + last..i + last + LANES]);
+        let mut mask = (firsts.simd_eq(first_splat) & lasts.simd_eq(last_splat)).to_bitmask();
+
+        while mask != 0 {
+            let pos = i + mask.trailing_zeros() as usize;
+            if haystack[pos + 1..pos + last] == needle[1..last] {
+                return Some(pos);
+            }
+
+            mask &= mask - 1;
+        }
+
+        i += LANES;
+    }
+
+    haystack[i..]
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| i + p)
+}
+
+/// Count the occurrences of the byte in the haystack, e.g. to count lines.
+#[inline]
+pub fn count_byte(haystack: &[u8], needle: u8) -> usize {
+    let splat = Chunk::splat(needle);
+    let (chunks, rest) = haystack.as_chunks::<LANES>();
+
+    let count = chunks
+        .iter()
+        .map(|chunk| {
+            Chunk::from_array(*chunk)
+                .simd_eq(splat)
+                .to_bitmask()
+                .count_ones() as usize
+        })
+        .sum::<usize>();
+
+    count + rest.iter().filter(|v| **v == needle).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+
+    fn haystack() -> Vec<u8> {
+        (0..1000u32)
+            .map(|i| b"abcdefghij\n, "[(i * 7 % 13) as usize])
+            .collect()
+    }
+
+    #[test]
+    fn scans_match_the_naive_versions() {
+        let haystack = haystack();
+
+        for len in 0..haystack.len() {
+            let h = &haystack[..len];
+            for b in b"aej\n,xyz".iter() {
+                assert_eq!(find_byte(h, *b), h.iter().position(|v| v == b));
+                assert_eq!(count_byte(h, *b), h.iter().filter(|v| *v == b).count());
+            }
+
+            assert_eq!(
+                find_either_byte(h, b"x\n,"),
+                h.iter().position(|v| b"x\n,".contains(v))
+            );
+            for needle in [b"ic".as_slice(), b"d\ne", b"hbicj", b"f g", b"jc", b"zz"] {
+                assert_eq!(
+                    find_subslice(h, needle),
+                    h.windows(needle.len()).position(|w| w == needle),
+                    "needle: {:?}, len {}",
+                    needle,
+                    len,
+                );
+            }
+        }
+    }
+
+    #[bench]
+    fn bench_find_subslice(b: &mut Bencher) {
+        let mut haystack = haystack().repeat(100);
+        haystack.extend_from_slice(b"needle");
+
+        b.iter(|| find_subslice(&haystack, b"needle"));
+    }
+
+    #[bench]
+    fn bench_find_subslice_naive(b: &mut Bencher) {
+        let mut haystack = haystack().repeat(100);
+        haystack.extend_from_slice(b"needle");
+
+        b.iter(|| haystack.windows(6).position(|w| w == b"needle"));
+    }
+}